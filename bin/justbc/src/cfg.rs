@@ -1,12 +1,16 @@
 use {
+    gen_tui::widgets::image::Protocol,
     input::binds::Bindings,
     serde::{
         de::{self, IntoDeserializer},
         Deserialize,
         Serialize,
     },
-    std::{fs, path::Path},
-    structopt::StructOpt,
+    std::{
+        fs,
+        path::{Path, PathBuf},
+    },
+    structopt::{clap::ArgMatches, StructOpt},
     structopt_toml::StructOptToml,
     tui::style::Color,
 };
@@ -19,15 +23,25 @@ pub struct Config {
     #[serde(default)]
     #[structopt(flatten)]
     pub gfx: Graphics,
+    #[serde(default)]
+    #[structopt(flatten)]
+    pub net: Network,
 }
 
 impl Config {
-    pub fn load(cfg_file: impl AsRef<Path>) -> crate::Result<Config> {
+    /// Loads the config from the provided file,
+    /// overriding it with the command line arguments.
+    pub fn load(
+        cfg_file: impl AsRef<Path>,
+        from_args: Config,
+        args: &ArgMatches,
+    ) -> crate::Result<Config> {
         if cfg_file.as_ref().exists() {
             let cfg = fs::read_to_string(&cfg_file)?;
-            Config::from_args_with_toml(&cfg).map_err(<_>::into)
+            let from_toml = toml::from_str(&cfg)?;
+            Ok(Config::merge(from_toml, from_args, args))
         } else {
-            Config::from_args_safe().map_err(<_>::into)
+            Ok(from_args)
         }
     }
 
@@ -60,6 +74,16 @@ pub struct General {
     /// The initial playback volume, from 0 to 1
     #[structopt(long, default_value = "0.5")]
     pub volume: f32,
+    /// The directory to save downloaded tracks in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[structopt(long, parse(from_os_str))]
+    pub download_dir: Option<PathBuf>,
+    /// How many seconds to crossfade between tracks for, if at all.
+    /// Tracks that follow each other on a release without silence in between
+    /// always play without a gap
+    #[serde(default)]
+    #[structopt(long, default_value = "0")]
+    pub crossfade: f32,
 }
 
 #[derive(StructOpt, StructOptToml, Serialize, Deserialize)]
@@ -75,6 +99,55 @@ pub struct Graphics {
         parse(try_from_str = parse_color)
     )]
     pub accent: Color,
+    /// How cover art is drawn: `blocks` draws it with text, `kitty` and
+    /// `sixel` draw images in terminals that support them, `auto` picks
+    /// the best of these and `off` doesn't draw it at all
+    #[serde(default)]
+    #[structopt(long, default_value = "auto", parse(try_from_str = parse_art))]
+    pub art: ArtMode,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ArtMode {
+    #[default]
+    Auto,
+    Blocks,
+    Kitty,
+    Sixel,
+    Off,
+}
+
+impl ArtMode {
+    /// Returns how art is drawn, unless it isn't.
+    pub fn protocol(self) -> Option<Protocol> {
+        match self {
+            ArtMode::Auto => Some(Protocol::detect()),
+            ArtMode::Blocks => Some(Protocol::HalfBlocks),
+            ArtMode::Kitty => Some(Protocol::Kitty),
+            ArtMode::Sixel => Some(Protocol::Sixel),
+            ArtMode::Off => None,
+        }
+    }
+}
+
+#[derive(StructOpt, StructOptToml, Serialize, Deserialize)]
+pub struct Network {
+    /// How long responses from Bandcamp are cached before they're
+    /// fetched again, in minutes
+    #[structopt(long, default_value = "60")]
+    pub cache_ttl: u64,
+    /// How much audio of streamed tracks is cached, in MiB
+    #[structopt(long, default_value = "512")]
+    pub audio_cache_size: u64,
+    /// How long to keep trying to reconnect when the connection
+    /// to a track drops, in seconds
+    #[structopt(long, default_value = "30")]
+    pub reconnect_budget: u64,
+    /// Only use cached responses instead of accessing the network
+    #[serde(skip)]
+    #[structopt(long)]
+    pub offline: bool,
 }
 
 #[allow(unused)]
@@ -105,3 +178,7 @@ enum ColorDef {
 fn parse_color(s: &str) -> Result<Color, de::value::Error> {
     ColorDef::deserialize(s.into_deserializer())
 }
+
+fn parse_art(s: &str) -> Result<ArtMode, de::value::Error> {
+    ArtMode::deserialize(s.into_deserializer())
+}
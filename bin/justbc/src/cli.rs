@@ -0,0 +1,38 @@
+use {
+    crate::{cfg::Config, ctl::Ctl, lookup::Lookup},
+    serde::{de::IntoDeserializer, Deserialize},
+    std::path::PathBuf,
+    structopt::StructOpt,
+};
+
+/// A third-party Bandcamp player that runs in the terminal
+#[derive(StructOpt)]
+#[structopt(name = "justbc")]
+pub struct Args {
+    #[structopt(flatten)]
+    pub config: Config,
+    #[structopt(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(StructOpt)]
+pub enum Command {
+    /// Controls a running instance
+    Ctl(Ctl),
+    /// Remembers the tagged mp3 files in the provided directories,
+    /// so they're played instead of being streamed
+    Import {
+        #[structopt(parse(from_os_str), required = true)]
+        dirs: Vec<PathBuf>,
+    },
+    #[structopt(flatten)]
+    Lookup(Lookup),
+}
+
+/// Parses a kebab-case variant name, like those used in the config file.
+pub fn parse_kebab<T>(s: &str) -> Result<T, serde::de::value::Error>
+where
+    T: for<'de> Deserialize<'de>,
+{
+    T::deserialize(s.into_deserializer())
+}
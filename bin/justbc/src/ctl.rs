@@ -0,0 +1,107 @@
+use {
+    crate::cli::parse_kebab,
+    bandcamp_api::data::releases::ReleaseKind,
+    remote::{Position, Request, Response, Status},
+    std::path::Path,
+    structopt::StructOpt,
+};
+
+#[derive(StructOpt)]
+pub enum Ctl {
+    /// Runs one of the commands that can be bound to keys, e.g. toggle-play
+    Command {
+        #[structopt(parse(try_from_str = parse_kebab))]
+        command: input::Command,
+    },
+    /// Plays a track of a release
+    Play {
+        /// The id of the release
+        release: u64,
+        /// The id of the artist or label the release belongs to
+        #[structopt(long)]
+        outlet: u64,
+        /// Whether the release is an album or a track
+        #[structopt(long, default_value = "album", parse(try_from_str = parse_kebab))]
+        kind: ReleaseKind,
+        /// The position of the track to play, starting at 0
+        #[structopt(long, default_value = "0")]
+        track: usize,
+    },
+    /// Seeks to a position in the current track, e.g. 1:23
+    Seek { position: Position },
+    /// Sets the playback volume, from 0 to 1
+    Volume { volume: f32 },
+    /// Shows what's playing and what's queued
+    Status {
+        /// Print the status as JSON
+        #[structopt(long)]
+        json: bool,
+    },
+}
+
+impl Ctl {
+    pub fn run(self, socket: impl AsRef<Path>) -> crate::Result {
+        let json = matches!(self, Ctl::Status { json: true });
+
+        match remote::send(socket, &self.into_request())? {
+            Response::Ok => Ok(()),
+            Response::Error { message } => Err(message.into()),
+            Response::Status(status) if json => {
+                println!("{}", serde_json::to_string_pretty(&status)?);
+                Ok(())
+            }
+            Response::Status(status) => {
+                print_status(&status);
+                Ok(())
+            }
+        }
+    }
+
+    fn into_request(self) -> Request {
+        match self {
+            Ctl::Command { command } => Request::Command { command },
+            Ctl::Play {
+                release,
+                outlet,
+                kind,
+                track,
+            } => Request::Play {
+                release,
+                outlet,
+                kind,
+                track,
+            },
+            Ctl::Seek { position } => Request::Seek { position },
+            Ctl::Volume { volume } => Request::Volume { volume },
+            Ctl::Status { .. } => Request::Status,
+        }
+    }
+}
+
+fn print_status(status: &Status) {
+    let current = status.current.and_then(|idx| status.queue.get(idx));
+
+    match current {
+        Some(track) => println!(
+            "{} {} / {}",
+            if status.paused { "paused" } else { "playing" },
+            status.elapsed,
+            track.duration
+        ),
+        None => println!("nothing playing"),
+    }
+
+    for (idx, track) in status.queue.iter().enumerate() {
+        println!(
+            "{} {} - {} ({})",
+            if Some(idx) == status.current {
+                '>'
+            } else {
+                ' '
+            },
+            track.artist,
+            track.title,
+            track.duration
+        )
+    }
+}
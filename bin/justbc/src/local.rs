@@ -0,0 +1,235 @@
+use {
+    crate::play::QueuedTrack,
+    bandcamp_api::data::releases::Stream,
+    fetch::download::Download,
+    id3::{Tag, TagLike},
+    serde::{Deserialize, Serialize},
+    std::{
+        fs,
+        path::{Path, PathBuf},
+    },
+};
+
+/// Copies of tracks on disk, which are played instead of
+/// streaming the tracks if they're still around.
+#[derive(Default, Serialize, Deserialize)]
+pub struct LocalFiles {
+    #[serde(default)]
+    tracks: Vec<LocalTrack>,
+    #[serde(skip)]
+    file: PathBuf,
+}
+
+#[derive(Serialize, Deserialize)]
+struct LocalTrack {
+    /// The id of the track on Bandcamp, if it's known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    id: Option<u64>,
+    artist: String,
+    album: String,
+    title: String,
+    path: PathBuf,
+}
+
+impl LocalTrack {
+    fn matches(&self, track: &QueuedTrack) -> bool {
+        fn eq(a: &str, b: &str) -> bool {
+            a.trim().to_lowercase() == b.trim().to_lowercase()
+        }
+
+        eq(&self.title, &track.track.title)
+            && eq(&self.artist, &track.artist)
+            && eq(&self.album, &track.album)
+    }
+}
+
+impl LocalFiles {
+    pub fn load(file: PathBuf) -> crate::Result<LocalFiles> {
+        let local = if file.exists() {
+            toml::from_str(&fs::read_to_string(&file)?)?
+        } else {
+            LocalFiles::default()
+        };
+
+        Ok(LocalFiles { file, ..local })
+    }
+
+    pub fn save(&self) -> crate::Result {
+        if let Some(dir) = self.file.parent() {
+            fs::create_dir_all(dir)?
+        }
+
+        fs::write(&self.file, toml::to_string_pretty(self)?)?;
+
+        Ok(())
+    }
+
+    /// Returns the path of a local copy of the provided track, if there is one.
+    /// Copies are matched by the track's id first, then by its tags.
+    pub fn find(&self, track: &QueuedTrack) -> Option<&Path> {
+        let id = track.track.stream.as_ref().and_then(Stream::track_id);
+
+        self.tracks
+            .iter()
+            .filter(|local| local.id.is_some() && local.id == id)
+            .chain(self.tracks.iter().filter(|local| local.matches(track)))
+            .map(|local| local.path.as_path())
+            .find(|path| path.is_file())
+    }
+
+    /// Remembers a track that has been downloaded.
+    pub fn add(&mut self, download: &Download) {
+        self.insert(LocalTrack {
+            id: download.stream.track_id(),
+            artist: download.tags.artist.clone(),
+            album: download.tags.album.clone(),
+            title: download.tags.title.clone(),
+            path: download.path.clone(),
+        })
+    }
+
+    /// Remembers all tagged mp3 files in the provided directory
+    /// and its subdirectories, returning how many there were.
+    pub fn import(&mut self, dir: &Path) -> crate::Result<usize> {
+        let mut found = 0;
+
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+
+            if path.is_dir() {
+                found += self.import(&path)?;
+                continue
+            }
+
+            if path.extension().is_none_or(|ext| ext != "mp3") {
+                continue
+            }
+
+            let tag = match Tag::read_from_path(&path) {
+                Ok(tag) => tag,
+                Err(_) => continue,
+            };
+
+            if let (Some(artist), Some(album), Some(title)) =
+                (tag.artist(), tag.album(), tag.title())
+            {
+                self.insert(LocalTrack {
+                    id: None,
+                    artist: artist.into(),
+                    album: album.into(),
+                    title: title.into(),
+                    path: fs::canonicalize(&path)?,
+                });
+
+                found += 1
+            }
+        }
+
+        Ok(found)
+    }
+
+    fn insert(&mut self, track: LocalTrack) {
+        self.tracks.retain(|local| local.path != track.path);
+        self.tracks.push(track)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::*,
+        crate::play::{test::release, Queue},
+        id3::Version,
+    };
+
+    fn dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("justbc-local-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        dir
+    }
+
+    /// The first track of the release with id 1, which is titled `track 0`.
+    fn track() -> QueuedTrack {
+        let mut queue = Queue::default();
+        queue.set_release(&release(1, 1), 0);
+
+        let mut track = queue.current().unwrap().clone();
+        track.track.stream = Some(Stream {
+            mp3_128: "https://t4.bcbits.com/stream/0/mp3-128/1234"
+                .parse()
+                .unwrap(),
+        });
+
+        track
+    }
+
+    fn local(id: Option<u64>, artist: &str, title: &str, path: &Path) -> LocalTrack {
+        fs::write(path, b"").unwrap();
+
+        LocalTrack {
+            id,
+            artist: artist.into(),
+            album: "release 1".into(),
+            title: title.into(),
+            path: path.into(),
+        }
+    }
+
+    #[test]
+    fn find() {
+        let dir = dir("find");
+        let track = track();
+        let mut files = LocalFiles::default();
+
+        files.insert(local(None, "artist", "track 1", &dir.join("other.mp3")));
+        assert_eq!(files.find(&track), None);
+
+        let by_tags = dir.join("tags.mp3");
+        files.insert(local(None, " ARTIST ", "Track 0", &by_tags));
+        assert_eq!(files.find(&track), Some(by_tags.as_path()));
+
+        // the id takes precedence over the tags
+        let by_id = dir.join("id.mp3");
+        files.insert(local(Some(1234), "someone", "something", &by_id));
+        assert_eq!(files.find(&track), Some(by_id.as_path()));
+
+        // copies that have been deleted since are skipped
+        fs::remove_file(&by_id).unwrap();
+        assert_eq!(files.find(&track), Some(by_tags.as_path()));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn import() {
+        let dir = dir("import");
+        fs::create_dir_all(dir.join("album")).unwrap();
+
+        let tag = |path: &Path, title: &str| {
+            let mut tag = Tag::new();
+            tag.set_artist("Artist");
+            tag.set_album("Release 1");
+            tag.set_title(title);
+
+            fs::write(path, b"").unwrap();
+            tag.write_to_path(path, Version::Id3v24).unwrap();
+        };
+
+        let copy = dir.join("album").join("01 track 0.mp3");
+        tag(&copy, "track 0");
+        tag(&dir.join("notes.txt"), "track 0");
+        fs::write(dir.join("untagged.mp3"), b"").unwrap();
+
+        let mut files = LocalFiles::default();
+
+        assert_eq!(files.import(&dir).unwrap(), 1);
+        assert_eq!(
+            files.find(&track()),
+            Some(fs::canonicalize(&copy).unwrap().as_path())
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,331 @@
+use {
+    crate::cli::parse_kebab,
+    bandcamp_api::{
+        data::{
+            common::Id,
+            fans::{Fan, FanArgs},
+            outlets::{Outlet, OutletKind},
+            releases::{Release, ReleaseArgs, ReleaseKind},
+            search::{Search, SearchResult},
+        },
+        Api,
+    },
+    bc_tui::tracks::Time,
+    serde::Serialize,
+    std::{
+        fmt::Display,
+        io::{self, Write},
+    },
+    structopt::StructOpt,
+};
+
+/// Queries Bandcamp without starting the player.
+#[derive(StructOpt)]
+pub enum Lookup {
+    /// Searches for artists, labels, releases and fans
+    Search {
+        query: String,
+        #[structopt(flatten)]
+        output: Output,
+    },
+    /// Shows a release along with its tracks
+    Release {
+        /// The id of the release
+        id: u64,
+        /// The id of the artist or label the release belongs to
+        #[structopt(long)]
+        outlet: u64,
+        /// Whether the release is an album or a track
+        #[structopt(long, default_value = "album", parse(try_from_str = parse_kebab))]
+        kind: ReleaseKind,
+        #[structopt(flatten)]
+        output: Output,
+    },
+    /// Shows an artist or label along with its discography
+    Outlet {
+        /// The id of the artist or label
+        id: u64,
+        #[structopt(flatten)]
+        output: Output,
+    },
+    /// Shows a fan along with their collection
+    Fan {
+        /// The id of the fan
+        id: u64,
+        /// The position in the collection to start at
+        #[structopt(long, default_value = "0")]
+        start: u16,
+        /// How many items of the collection to show
+        #[structopt(long, default_value = "25")]
+        count: u16,
+        #[structopt(flatten)]
+        output: Output,
+    },
+}
+
+impl Lookup {
+    pub fn run(self, api: &Api) -> crate::Result {
+        match self {
+            Lookup::Search { query, output } => output.print(&api.query::<Search, _>(&*query)?),
+            Lookup::Release {
+                id,
+                outlet,
+                kind,
+                output,
+            } => {
+                let args = ReleaseArgs {
+                    id: Id::new(id),
+                    kind,
+                    outlet: Id::new(outlet),
+                };
+
+                output.print(&api.query::<Release, _>(&args)?)
+            }
+            Lookup::Outlet { id, output } => {
+                output.print(&api.query::<Outlet, _>(&Id::<Outlet>::new(id))?)
+            }
+            Lookup::Fan {
+                id,
+                start,
+                count,
+                output,
+            } => {
+                let args = FanArgs {
+                    id: Id::new(id),
+                    start,
+                    count,
+                };
+
+                output.print(&api.query::<Fan, _>(&args)?)
+            }
+        }
+    }
+}
+
+#[derive(StructOpt)]
+pub struct Output {
+    /// Print the result as JSON instead of a table
+    #[structopt(long)]
+    json: bool,
+}
+
+impl Output {
+    fn print<T: Serialize + Print>(&self, data: &T) -> crate::Result {
+        self.write(data, &mut io::stdout().lock())
+    }
+
+    fn write<T: Serialize + Print>(&self, data: &T, out: &mut dyn Write) -> crate::Result {
+        if self.json {
+            writeln!(out, "{}", serde_json::to_string_pretty(data)?)?
+        } else {
+            data.print(out)?
+        }
+
+        Ok(())
+    }
+}
+
+/// Prints data in a human-readable form.
+trait Print {
+    fn print(&self, out: &mut dyn Write) -> io::Result<()>;
+}
+
+impl Print for Search {
+    fn print(&self, out: &mut dyn Write) -> io::Result<()> {
+        let mut table = Table::new(&["type", "id", "name", "by"]);
+
+        for result in &self.results {
+            match result {
+                SearchResult::Outlet(o) => table.row(&[&outlet_kind(o.kind), &o.id, &o.name, &""]),
+                SearchResult::Album(a) => table.row(&[&"album", &a.id, &a.name, &a.artist]),
+                SearchResult::Track(t) => table.row(&[&"track", &t.id, &t.name, &t.artist]),
+                SearchResult::Fan(f) => table.row(&[&"fan", &f.id, &f.name, &""]),
+            }
+        }
+
+        table.print(out)
+    }
+}
+
+impl Print for Release {
+    fn print(&self, out: &mut dyn Write) -> io::Result<()> {
+        let info = &self.info;
+
+        writeln!(out, "{} by {}", info.title, info.artist)?;
+        writeln!(
+            out,
+            "{}, released {}",
+            info.kind.to_string().to_lowercase(),
+            info.release_date.fmt_long()
+        )?;
+
+        if let Some(label) = &info.label {
+            writeln!(out, "on {}", label)?
+        }
+
+        if !info.tags.is_empty() {
+            let tags = info.tags.iter().map(|tag| tag.name.as_str());
+            writeln!(out, "tagged {}", tags.collect::<Vec<_>>().join(", "))?
+        }
+
+        writeln!(out)?;
+
+        let mut table = Table::new(&["#", "title", "artist", "duration"]);
+
+        for (idx, track) in self.tracks.iter().enumerate() {
+            let number = track.track_num.map_or(idx + 1, |num| num as usize);
+            let artist = track.artist.as_deref().unwrap_or(&info.artist);
+
+            table.row(&[&number, &track.title, &artist, &Time::from(track.duration)])
+        }
+
+        table.print(out)?;
+
+        for text in info.about.iter().chain(&info.credits) {
+            writeln!(out)?;
+            writeln!(out, "{}", text)?
+        }
+
+        Ok(())
+    }
+}
+
+impl Print for Outlet {
+    fn print(&self, out: &mut dyn Write) -> io::Result<()> {
+        let info = &self.info;
+
+        match &info.location {
+            Some(location) => writeln!(out, "{} ({})", info.name, location)?,
+            None => writeln!(out, "{}", info.name)?,
+        }
+
+        writeln!(out, "{}", outlet_kind(info.kind))?;
+
+        if let Some(bio) = &info.bio {
+            writeln!(out)?;
+            writeln!(out, "{}", bio)?
+        }
+
+        writeln!(out)?;
+
+        let mut table = Table::new(&["type", "id", "title", "artist"]);
+
+        for release in &self.discography {
+            table.row(&[
+                &release.kind.to_string().to_lowercase(),
+                &release.id,
+                &release.title,
+                &release.artist.as_deref().unwrap_or(&info.name),
+            ])
+        }
+
+        table.print(out)
+    }
+}
+
+impl Print for Fan {
+    fn print(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "{} ({})", self.name, self.location)?;
+
+        if !self.bio.is_empty() {
+            writeln!(out)?;
+            writeln!(out, "{}", self.bio)?
+        }
+
+        writeln!(out)?;
+
+        let mut table = Table::new(&["type", "id", "title", "artist", "artist id"]);
+
+        for collected in &self.collection {
+            table.row(&[
+                &collected.kind.to_string().to_lowercase(),
+                &collected.id,
+                &collected.title,
+                &collected.artist,
+                &collected.artist_id,
+            ])
+        }
+
+        table.print(out)
+    }
+}
+
+/// Rows of text that are printed in aligned columns.
+struct Table {
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    fn new(header: &[&str]) -> Table {
+        Table {
+            rows: vec![header.iter().map(|s| s.to_uppercase()).collect()],
+        }
+    }
+
+    fn row(&mut self, cells: &[&dyn Display]) {
+        self.rows
+            .push(cells.iter().map(ToString::to_string).collect())
+    }
+
+    fn print(&self, out: &mut dyn Write) -> io::Result<()> {
+        let widths: Vec<_> = (0..self.rows[0].len())
+            .map(|col| {
+                self.rows
+                    .iter()
+                    .map(|row| row[col].chars().count())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        for row in &self.rows {
+            let line = row
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{:1$}", cell, width))
+                .collect::<Vec<_>>()
+                .join("  ");
+
+            writeln!(out, "{}", line.trim_end())?
+        }
+
+        Ok(())
+    }
+}
+
+fn outlet_kind(kind: OutletKind) -> &'static str {
+    match kind {
+        OutletKind::Artist => "artist",
+        OutletKind::Label => "label",
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {super::*, crate::play::test::release};
+
+    fn output(json: bool) -> String {
+        let mut out = vec![];
+        Output { json }.write(&release(1, 2), &mut out).unwrap();
+
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn table() {
+        assert_eq!(
+            output(false),
+            "release 1 by artist\nalbum, released 1 January 2000\n\n#  TITLE    ARTIST  \
+             DURATION\n1  track 0  artist  1:00\n2  track 1  artist  1:00\n"
+        );
+    }
+
+    #[test]
+    fn json() {
+        let json: serde_json::Value = serde_json::from_str(&output(true)).unwrap();
+
+        assert_eq!(json["title"], "release 1");
+        assert_eq!(json["artist"], "artist");
+        assert_eq!(json["tracks"][1]["title"], "track 1");
+    }
+}
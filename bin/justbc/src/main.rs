@@ -1,17 +1,29 @@
 mod cfg;
+mod cli;
+mod ctl;
+mod local;
+mod lookup;
 mod play;
+mod session;
 mod state;
 mod term;
 mod ui;
 
 use {
-    bandcamp_api::Api,
+    bandcamp_api::{cache::Cache, Api},
+    bc_track::{Reconnect, SegmentCache},
     cfg::Config,
+    cli::{Args, Command},
     directories::ProjectDirs,
     event::*,
     fetch::Fetcher,
-    state::State,
-    std::{error::Error, thread, time::Duration},
+    gen_tui::widgets::image::Graphics,
+    local::LocalFiles,
+    play::Queue,
+    session::Session,
+    state::{LibraryState, State},
+    std::{error::Error, path::PathBuf, thread, time::Duration},
+    structopt::StructOpt,
     term::Terminal,
 };
 
@@ -27,27 +39,102 @@ fn run() -> Result {
     let dirs = ProjectDirs::from("io.github", "reslario", "justbc")
         .ok_or("couldn't get program directories")?;
 
+    let matches = Args::clap().get_matches();
+    let args = Args::from_clap(&matches);
+
     let cfg_file = dirs.config_dir().join("config.toml");
 
-    let mut config = Config::load(&cfg_file)?;
+    let mut config = Config::load(&cfg_file, args.config, &matches)?;
+
+    let api = api(&config.net, &dirs);
+
+    let local_file = dirs.data_dir().join("local.toml");
+
+    if let Some(command) = args.command {
+        return match command {
+            Command::Ctl(ctl) => ctl.run(socket_path(&dirs)),
+            Command::Import { dirs } => import(&dirs, local_file),
+            Command::Lookup(lookup) => lookup.run(&api),
+        }
+    }
+
+    let library = LibraryState::load(dirs.data_dir().join("library.toml"))?;
+
+    let queue_file = dirs.config_dir().join("queue.toml");
+
+    let queue = Queue::load(&queue_file)?;
+
+    let local = LocalFiles::load(local_file)?;
+
+    let session_file = dirs.config_dir().join("session.toml");
+
+    let session = Session::load(&session_file)?;
 
     let mut terminal = term::terminal()?;
 
-    let (api, receiver) = Fetcher::new(Api::new());
+    let (mut fetcher, receiver) = Fetcher::new(api);
+
+    // streaming still works without the cache
+    let audio_cache = dirs.cache_dir().join("audio");
+    if let Ok(cache) = SegmentCache::new(audio_cache, config.net.audio_cache_size << 20) {
+        fetcher.cache_segments(cache)
+    }
+
+    fetcher.reconnect(Reconnect::within(Duration::from_secs(
+        config.net.reconnect_budget,
+    )));
+
+    let mut state = State::new(config.state, library, queue, local, fetcher);
+
+    state.restore(session);
 
-    let mut state = State::new(config.state, api);
+    // there can only be one instance listening, so any others
+    // simply can't be controlled remotely
+    let remote = remote::Server::bind(socket_path(&dirs)).ok();
 
-    let events = Events::new(receiver)?;
+    let events = Events::new(receiver, remote)?;
 
     main_loop(events, &mut state, &mut terminal, &config.gfx)?;
 
     terminal.clear()?;
 
+    state.core.queue.save(queue_file)?;
+
+    state.session().save(session_file)?;
+
     config.state = state.into_config();
 
     config.save(cfg_file)
 }
 
+fn api(net: &cfg::Network, dirs: &ProjectDirs) -> Api {
+    let ttl = Duration::from_secs(net.cache_ttl * 60);
+    let api = Api::new().with_cache(Cache::new(dirs.cache_dir().join("api"), ttl));
+
+    if net.offline {
+        api.offline()
+    } else {
+        api
+    }
+}
+
+fn import(dirs: &[PathBuf], local_file: PathBuf) -> Result {
+    let mut local = LocalFiles::load(local_file)?;
+
+    for dir in dirs {
+        let found = local.import(dir)?;
+        println!("found {} tracks in {}", found, dir.display())
+    }
+
+    local.save()
+}
+
+fn socket_path(dirs: &ProjectDirs) -> PathBuf {
+    dirs.runtime_dir()
+        .unwrap_or_else(|| dirs.data_dir())
+        .join("remote.sock")
+}
+
 fn main_loop(
     events: Events,
     state: &mut State,
@@ -55,13 +142,21 @@ fn main_loop(
     gfx: &cfg::Graphics,
 ) -> Result {
     let refresh = Duration::from_millis(gfx.refresh);
+    let mut graphics = gfx.art.protocol().map(|protocol| match term::cell_size() {
+        Some((width, height)) => Graphics::new(protocol).cell_size(width, height),
+        None => Graphics::new(protocol),
+    });
 
     'main: loop {
         for event in events.iter() {
             match event {
                 Event::Input(evt) => state.input(evt),
-                Event::Response(resp) => state.set_response(resp),
+                Event::Response(resp) => state.set_response(*resp),
                 Event::MediaKey(key) => state.handle_media_key(key),
+                Event::Remote(call) => {
+                    let response = state.handle_remote(&call.request);
+                    call.reply(response)
+                }
                 Event::DeviceUpdated => state.update_device(),
                 Event::Terminate => break 'main Ok(()),
             }
@@ -69,7 +164,14 @@ fn main_loop(
 
         state.update();
 
-        terminal.draw(|frame| ui::draw(frame, state, gfx))?;
+        events.publish(&state.playback());
+
+        terminal.draw(|frame| ui::draw(frame, state, gfx, graphics.as_mut()))?;
+
+        if let Some(graphics) = graphics.as_mut() {
+            let screen = terminal.size()?;
+            graphics.flush(terminal.backend_mut(), screen)?
+        }
 
         thread::sleep(refresh)
     }
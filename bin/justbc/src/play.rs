@@ -1,23 +1,207 @@
-use {bandcamp_api::data::releases::Track, std::time::Duration};
+use {
+    bandcamp_api::data::{
+        common::{Art, Id},
+        outlets::Outlet,
+        releases::{Lyrics, Release, ReleaseArgs, ReleaseKind, Track},
+    },
+    bc_tui::tracks::Repeat,
+    serde::{Deserialize, Serialize},
+    std::{fs, path::Path, time::Duration},
+};
 
-#[derive(Default, Debug)]
+/// A track in the [Queue](Queue), along with the release it's from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedTrack {
+    pub artist: String,
+    pub album: String,
+    pub release: Id<Release>,
+    pub kind: ReleaseKind,
+    pub outlet: Id<Outlet>,
+    /// The position of the track within its release.
+    pub number: usize,
+    /// Whether the track was queued explicitly, as opposed to
+    /// being part of the release that's currently playing.
+    #[serde(default)]
+    pub queued: bool,
+    /// The cover art of the release.
+    #[serde(default)]
+    pub art: Option<Id<Art>>,
+    pub track: Track,
+}
+
+impl QueuedTrack {
+    fn from_release(release: &Release, number: usize, queued: bool) -> Option<QueuedTrack> {
+        release.tracks.get(number).map(|track| QueuedTrack {
+            track: track.clone(),
+            artist: track
+                .artist
+                .clone()
+                .unwrap_or_else(|| release.info.artist.clone()),
+            album: release.info.title.clone(),
+            release: release.info.id,
+            kind: release.info.kind,
+            outlet: release.info.band.id,
+            number,
+            queued,
+            art: release.info.art_id,
+        })
+    }
+
+    pub fn is_streamable(&self) -> bool {
+        self.track.streamable().is_some()
+    }
+
+    pub fn release_args(&self) -> ReleaseArgs {
+        ReleaseArgs {
+            id: self.release,
+            kind: self.kind,
+            outlet: self.outlet,
+        }
+    }
+}
+
+#[derive(Default, Debug, Serialize, Deserialize)]
 pub struct Queue {
-    tracks: Vec<Track>,
+    #[serde(default)]
     current: usize,
+    #[serde(default, with = "RepeatDef")]
+    repeat: Repeat,
+    /// The order in which the tracks are played when shuffling,
+    /// as indices into `tracks`.
+    #[serde(default)]
+    shuffle: Option<Vec<usize>>,
+    #[serde(default)]
+    tracks: Vec<QueuedTrack>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "Repeat", rename_all = "kebab-case")]
+enum RepeatDef {
+    Off,
+    One,
+    All,
 }
 
 impl Queue {
-    #[allow(clippy::ptr_arg)]
-    pub fn clone_tracks(&mut self, tracks: &Vec<Track>) {
-        self.tracks.clone_from(tracks);
-        self.current = 0;
+    pub fn load(file: impl AsRef<Path>) -> crate::Result<Queue> {
+        if file.as_ref().exists() {
+            let toml = fs::read_to_string(file)?;
+            toml::from_str(&toml).map_err(<_>::into)
+        } else {
+            Ok(<_>::default())
+        }
+    }
+
+    pub fn save(&self, file: impl AsRef<Path>) -> crate::Result {
+        if let Some(dir) = file.as_ref().parent() {
+            fs::create_dir_all(dir)?
+        }
+
+        fs::write(file, toml::to_string_pretty(self)?)?;
+
+        Ok(())
+    }
+
+    /// Replaces the tracks of the previously playing release with the
+    /// ones from the provided release, starting at `start`, or the first
+    /// track after it that can be streamed.
+    /// Tracks that were queued explicitly and haven't been played yet are
+    /// kept and will play after the starting track.
+    pub fn set_release(&mut self, release: &Release, start: usize) {
+        let upcoming = self
+            .tracks
+            .split_off((self.current + 1).min(self.tracks.len()));
+
+        self.tracks = (0..release.tracks.len())
+            .filter_map(|number| QueuedTrack::from_release(release, number, false))
+            .collect();
+        self.current = start;
+
+        let insert_at = (start + 1).min(self.tracks.len());
+
+        self.tracks.splice(
+            insert_at..insert_at,
+            upcoming.into_iter().filter(|track| track.queued),
+        );
+
+        if self.shuffle.is_some() {
+            self.shuffle = Some(self.shuffled())
+        }
+
+        if !self.current().is_some_and(QueuedTrack::is_streamable) {
+            self.skip()
+        }
+    }
+
+    /// Adds a track of the provided release to the end of the queue.
+    pub fn append(&mut self, release: &Release, number: usize) {
+        if let Some(track) = QueuedTrack::from_release(release, number, true) {
+            self.tracks.push(track);
+
+            let index = self.tracks.len() - 1;
+            let position = self.position();
+
+            if let Some(order) = &mut self.shuffle {
+                order.insert(fastrand::usize(position + 1..=order.len()), index)
+            }
+        }
+    }
+
+    /// Adds a track of the provided release right after the current one.
+    pub fn play_next(&mut self, release: &Release, number: usize) {
+        if let Some(track) = QueuedTrack::from_release(release, number, true) {
+            let at = (self.current + 1).min(self.tracks.len());
+            self.tracks.insert(at, track);
+
+            let position = self.position();
+
+            if let Some(order) = &mut self.shuffle {
+                order
+                    .iter_mut()
+                    .filter(|idx| **idx >= at)
+                    .for_each(|idx| *idx += 1);
+                order.insert((position + 1).min(order.len()), at)
+            }
+        }
+    }
+
+    /// Removes the track at the provided index, unless it's the current one.
+    /// Returns whether a track was removed.
+    pub fn remove(&mut self, index: usize) -> bool {
+        if index == self.current || index >= self.tracks.len() {
+            return false
+        }
+
+        self.tracks.remove(index);
+
+        if index < self.current {
+            self.current -= 1
+        }
+
+        if let Some(order) = &mut self.shuffle {
+            order.retain(|idx| *idx != index);
+            order
+                .iter_mut()
+                .filter(|idx| **idx > index)
+                .for_each(|idx| *idx -= 1);
+        }
+
+        true
+    }
+
+    pub fn tracks(&self) -> &[QueuedTrack] {
+        &self.tracks
+    }
+
+    pub fn len(&self) -> usize {
+        self.tracks.len()
     }
 
     pub fn set_track(&mut self, index: usize) {
         self.current = index
     }
 
-    pub fn current(&self) -> Option<&Track> {
+    pub fn current(&self) -> Option<&QueuedTrack> {
         self.tracks.get(self.current)
     }
 
@@ -25,21 +209,137 @@ impl Queue {
         self.current
     }
 
-    pub fn next(&self) -> Option<&Track> {
-        self.tracks.get(self.current + 1)
+    /// Returns the position of the current track within the provided
+    /// release, if it's from that release.
+    pub fn playing_in(&self, release: &Release) -> Option<usize> {
+        self.current()
+            .filter(|track| track.release == release.info.id)
+            .map(|track| track.number)
+    }
+
+    /// Replaces the queued tracks of the provided release with their
+    /// newer versions, e.g. to get streams that haven't expired yet.
+    pub fn update_release(&mut self, release: &Release) {
+        self.tracks
+            .iter_mut()
+            .filter(|queued| queued.release == release.info.id)
+            .for_each(|queued| {
+                if let Some(track) = release.tracks.get(queued.number) {
+                    let lyrics = queued.track.lyrics.take();
+
+                    queued.track = track.clone();
+                    queued.track.lyrics = queued.track.lyrics.take().or(lyrics)
+                }
+            })
+    }
+
+    /// Fills in the lyrics of the queued tracks that have them.
+    pub fn set_lyrics(&mut self, lyrics: &Lyrics) {
+        for queued in &mut self.tracks {
+            queued.track.set_lyrics(lyrics)
+        }
+    }
+
+    pub fn repeat(&self) -> Repeat {
+        self.repeat
+    }
+
+    pub fn cycle_repeat(&mut self) {
+        self.repeat = match self.repeat {
+            Repeat::Off => Repeat::All,
+            Repeat::All => Repeat::One,
+            Repeat::One => Repeat::Off,
+        }
+    }
+
+    pub fn is_shuffled(&self) -> bool {
+        self.shuffle.is_some()
+    }
+
+    /// Toggles shuffling. The current track stays the same either way.
+    pub fn toggle_shuffle(&mut self) {
+        self.shuffle = match self.shuffle {
+            Some(_) => None,
+            None => Some(self.shuffled()),
+        }
+    }
+
+    /// Returns a random order of the tracks, starting with the current one.
+    fn shuffled(&self) -> Vec<usize> {
+        let mut rest = (0..self.tracks.len())
+            .filter(|idx| *idx != self.current)
+            .collect::<Vec<_>>();
+
+        fastrand::shuffle(&mut rest);
+
+        std::iter::once(self.current)
+            .filter(|idx| *idx < self.tracks.len())
+            .chain(rest)
+            .collect()
+    }
+
+    /// Returns the position of the current track in the playing order.
+    fn position(&self) -> usize {
+        match &self.shuffle {
+            Some(order) => order
+                .iter()
+                .position(|idx| *idx == self.current)
+                .unwrap_or_default(),
+            None => self.current,
+        }
+    }
+
+    /// Returns the index of the track that's `offset` tracks away from
+    /// the current one in the playing order, wrapping around when
+    /// repeating all tracks.
+    fn step(&self, offset: isize) -> Option<usize> {
+        let len = self.tracks.len() as isize;
+        let mut position = self.position() as isize + offset;
+
+        if self.repeat == Repeat::All && len > 0 {
+            position = position.rem_euclid(len)
+        }
+
+        if !(0..len).contains(&position) {
+            return None
+        }
+
+        let position = position as usize;
+
+        match &self.shuffle {
+            Some(order) => order.get(position).copied(),
+            None => Some(position),
+        }
+    }
+
+    /// Returns the index of the closest track in the provided direction
+    /// that can be streamed, passing over the ones that can't.
+    fn step_streamable(&self, direction: isize) -> Option<usize> {
+        (1..=self.tracks.len() as isize)
+            .map_while(|n| self.step(n * direction))
+            .find(|idx| self.tracks[*idx].is_streamable())
     }
 
-    pub fn prev(&self) -> Option<&Track> {
-        self.tracks.get(self.current.checked_sub(1)?)
+    /// Returns the track that plays after the current one is over.
+    pub fn next(&self) -> Option<&QueuedTrack> {
+        match self.repeat {
+            Repeat::One => self.current(),
+            _ => self.tracks.get(self.step_streamable(1)?),
+        }
     }
 
-    pub fn prepare_next(&self, elapsed: Duration) -> Option<&Track> {
+    /// Returns the next track if it's time to fetch it, so it's ready by
+    /// the time it starts fading in, `crossfade` before the current one ends.
+    pub fn prepare_next(&self, elapsed: Duration, crossfade: Duration) -> Option<&QueuedTrack> {
+        const MARGIN: Duration = Duration::from_secs(5);
+
         if self
             .current()?
+            .track
             .duration
             .checked_sub(elapsed)
             .unwrap_or_default()
-            <= Duration::from_secs(5)
+            <= crossfade + MARGIN
         {
             self.next()
         } else {
@@ -47,15 +347,246 @@ impl Queue {
         }
     }
 
+    /// Moves on to the track that plays after the current one is over.
     pub fn advance(&mut self) {
-        if self.next().is_some() {
-            self.current += 1
+        if self.repeat != Repeat::One {
+            self.skip()
+        }
+    }
+
+    /// Moves on to the next track, even when repeating the current one.
+    pub fn skip(&mut self) {
+        if let Some(next) = self.step_streamable(1) {
+            self.current = next
         }
     }
 
     pub fn regress(&mut self) {
-        if self.prev().is_some() {
-            self.current -= 1;
+        if let Some(prev) = self.step_streamable(-1) {
+            self.current = prev
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use {
+        super::*,
+        bandcamp_api::data::{
+            common::{Date, Month},
+            releases::{Band, Info, Stream},
+        },
+    };
+
+    pub(crate) fn release(id: u64, tracks: usize) -> Release {
+        Release {
+            info: Info {
+                id: Id::new(id),
+                kind: ReleaseKind::Album,
+                band: Band {
+                    id: Id::new(0),
+                    name: "artist".into(),
+                    location: None,
+                },
+                artist: "artist".into(),
+                title: format!("release {}", id),
+                art_id: None,
+                about: None,
+                credits: None,
+                release_date: Date {
+                    day: std::num::NonZeroU8::new(1).unwrap(),
+                    month: Month::January,
+                    year: 2000,
+                },
+                tags: vec![],
+                label: None,
+                label_id: None,
+            },
+            tracks: (0..tracks)
+                .map(|n| Track {
+                    id: Some(Id::new(id * 100 + n as u64)),
+                    track_num: Some(n as u32 + 1),
+                    title: format!("track {}", n),
+                    artist: None,
+                    stream: Some(Stream {
+                        mp3_128: "a://b.c".parse().unwrap(),
+                    }),
+                    duration: Duration::from_secs(60),
+                    is_streamable: true,
+                    has_lyrics: false,
+                    lyrics: None,
+                })
+                .collect(),
+        }
+    }
+
+    fn titles(queue: &Queue) -> Vec<(u64, &str)> {
+        queue
+            .tracks()
+            .iter()
+            .map(|t| {
+                (
+                    t.release.to_string().parse().unwrap(),
+                    t.track.title.as_str(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn keeps_queued_tracks() {
+        let mut queue = Queue::default();
+
+        queue.set_release(&release(1, 3), 0);
+        queue.append(&release(2, 2), 1);
+        queue.play_next(&release(3, 1), 0);
+
+        queue.set_release(&release(4, 2), 0);
+
+        assert_eq!(queue.index(), 0);
+        assert_eq!(titles(&queue), [
+            (4, "track 0"),
+            (3, "track 0"),
+            (2, "track 1"),
+            (4, "track 1")
+        ]);
+    }
+
+    #[test]
+    fn remove() {
+        let mut queue = Queue::default();
+
+        queue.set_release(&release(1, 3), 1);
+
+        assert!(!queue.remove(1));
+        assert!(queue.remove(0));
+        assert_eq!(queue.index(), 0);
+        assert_eq!(queue.current().unwrap().track.title, "track 1");
+        assert!(!queue.remove(3));
+    }
+
+    #[test]
+    fn shuffle() {
+        let mut queue = Queue::default();
+
+        queue.set_release(&release(1, 10), 4);
+        queue.toggle_shuffle();
+
+        let mut order = vec![queue.index()];
+
+        while let Some(next) = queue.step(1) {
+            queue.skip();
+            order.push(next);
         }
+
+        assert_eq!(order[0], 4);
+        order.sort_unstable();
+        assert_eq!(order, (0..10).collect::<Vec<_>>());
+
+        let current = queue.index();
+        queue.toggle_shuffle();
+        assert_eq!(queue.index(), current);
+    }
+
+    #[test]
+    fn shuffled_play_next() {
+        let mut queue = Queue::default();
+
+        queue.set_release(&release(1, 5), 2);
+        queue.toggle_shuffle();
+        queue.play_next(&release(2, 1), 0);
+
+        assert_eq!(queue.next().unwrap().release, Id::new(2));
+        assert!(queue.remove(3));
+        assert_eq!(queue.shuffle.as_ref().unwrap().len(), queue.len());
+    }
+
+    #[test]
+    fn repeat() {
+        let mut queue = Queue::default();
+
+        queue.set_release(&release(1, 2), 1);
+        assert!(queue.next().is_none());
+
+        queue.cycle_repeat();
+        assert_eq!(queue.repeat(), Repeat::All);
+        assert_eq!(queue.next().unwrap().track.title, "track 0");
+
+        queue.cycle_repeat();
+        assert_eq!(queue.repeat(), Repeat::One);
+        queue.set_track(0);
+        queue.advance();
+        assert_eq!(queue.index(), 0);
+        queue.skip();
+        assert_eq!(queue.index(), 1);
+    }
+
+    #[test]
+    fn prepare_next() {
+        let mut queue = Queue::default();
+        let secs = Duration::from_secs;
+
+        queue.set_release(&release(1, 2), 0);
+
+        assert!(queue.prepare_next(secs(54), secs(0)).is_none());
+        assert_eq!(queue.prepare_next(secs(55), secs(0)).unwrap().number, 1);
+
+        // the track is there before the 10 second crossfade starts
+        assert_eq!(queue.prepare_next(secs(45), secs(10)).unwrap().number, 1);
+        assert!(queue.prepare_next(secs(44), secs(10)).is_none());
+    }
+
+    #[test]
+    fn skips_unstreamable() {
+        let mut queue = Queue::default();
+        let mut release = release(1, 4);
+
+        release.tracks[0].is_streamable = false;
+        release.tracks[2].stream = None;
+
+        queue.set_release(&release, 0);
+        assert_eq!(queue.index(), 1);
+        assert_eq!(queue.next().unwrap().track.title, "track 3");
+
+        queue.skip();
+        assert_eq!(queue.index(), 3);
+        queue.regress();
+        assert_eq!(queue.index(), 1);
+        queue.regress();
+        assert_eq!(queue.index(), 1);
+    }
+
+    #[test]
+    fn lyrics() {
+        let mut queue = Queue::default();
+
+        queue.set_release(&release(1, 2), 0);
+
+        let lyrics = Lyrics {
+            lyrics: std::iter::once((Id::new(101), "la la la".to_owned())).collect(),
+        };
+
+        queue.set_lyrics(&lyrics);
+        queue.update_release(&release(1, 2));
+
+        assert_eq!(queue.tracks()[0].track.lyrics, None);
+        assert_eq!(queue.tracks()[1].track.lyrics.as_deref(), Some("la la la"));
+    }
+
+    #[test]
+    fn roundtrip() {
+        let mut queue = Queue::default();
+
+        queue.set_release(&release(1, 3), 1);
+        queue.toggle_shuffle();
+        queue.cycle_repeat();
+
+        let toml = toml::to_string_pretty(&queue).unwrap();
+        let loaded = toml::from_str::<Queue>(&toml).unwrap();
+
+        assert_eq!(titles(&loaded), titles(&queue));
+        assert_eq!(loaded.index(), 1);
+        assert_eq!(loaded.shuffle, queue.shuffle);
+        assert_eq!(loaded.repeat(), Repeat::All);
     }
 }
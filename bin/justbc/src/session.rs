@@ -0,0 +1,41 @@
+use {
+    library::{Entry, SavedRelease},
+    serde::{Deserialize, Serialize},
+    std::{fs, path::Path},
+};
+
+/// What was going on when justbc was last closed,
+/// so it can be picked up again on the next launch.
+#[derive(Default, Serialize, Deserialize)]
+pub struct Session {
+    /// How far into the current track of the queue playback was, in seconds.
+    #[serde(default)]
+    pub elapsed: f64,
+    /// The last search, if its results were being explored.
+    pub search: Option<String>,
+    /// The release that was displayed next to the navigation.
+    pub release: Option<SavedRelease>,
+    /// What was being explored, unless it was a search.
+    pub explore: Option<Entry>,
+}
+
+impl Session {
+    pub fn load(file: impl AsRef<Path>) -> crate::Result<Session> {
+        if file.as_ref().exists() {
+            let toml = fs::read_to_string(file)?;
+            toml::from_str(&toml).map_err(<_>::into)
+        } else {
+            Ok(<_>::default())
+        }
+    }
+
+    pub fn save(&self, file: impl AsRef<Path>) -> crate::Result {
+        if let Some(dir) = file.as_ref().parent() {
+            fs::create_dir_all(dir)?
+        }
+
+        fs::write(file, toml::to_string_pretty(self)?)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,81 @@
+use {
+    bandcamp_api::data::common::{Art, Id},
+    fetch::{
+        art::{Cover, Picture},
+        Fetcher,
+    },
+    gen_tui::widgets::Pixels,
+    std::{
+        collections::{HashMap, VecDeque},
+        mem,
+        sync::Arc,
+    },
+};
+
+/// Cover art that has been fetched, by the id of the art.
+#[derive(Default)]
+pub struct ArtCache {
+    /// Art that's still being fetched or couldn't be fetched is `None`.
+    covers: HashMap<Id<Art>, Option<CoverArt>>,
+    /// The ids of the art in the order it was requested in.
+    order: VecDeque<Id<Art>>,
+    /// Art that has been removed since it was last checked.
+    evicted: Vec<Id<Art>>,
+}
+
+pub struct CoverArt {
+    pub small: Pixels,
+    pub full: Arc<Pixels>,
+}
+
+impl ArtCache {
+    const CAPACITY: usize = 32;
+
+    /// Starts fetching the art with the provided id,
+    /// unless that has happened already.
+    pub fn request(&mut self, id: Id<Art>, fetcher: &Fetcher) {
+        if self.covers.contains_key(&id) {
+            return
+        }
+
+        fetcher.art(id);
+
+        self.covers.insert(id, None);
+        self.order.push_back(id);
+
+        if self.order.len() > Self::CAPACITY {
+            if let Some(oldest) = self.order.pop_front() {
+                self.covers.remove(&oldest);
+                self.evicted.push(oldest)
+            }
+        }
+    }
+
+    /// Returns the art with the provided id, if it has been fetched.
+    pub fn get(&self, id: Id<Art>) -> Option<&CoverArt> {
+        self.covers.get(&id)?.as_ref()
+    }
+
+    /// Returns the art that has been removed from
+    /// the cache since the last call.
+    pub fn take_evicted(&mut self) -> Vec<Id<Art>> {
+        mem::take(&mut self.evicted)
+    }
+
+    /// Stores art that has been fetched, unless it's been evicted
+    /// from the cache in the meantime.
+    pub fn insert(&mut self, id: Id<Art>, cover: Cover) {
+        fn pixels(picture: Picture) -> Option<Pixels> {
+            Pixels::new(picture.width, picture.height, picture.rgb)
+        }
+
+        if let Some(entry) = self.covers.get_mut(&id) {
+            *entry = pixels(cover.small)
+                .zip(pixels(cover.full))
+                .map(|(small, full)| CoverArt {
+                    small,
+                    full: full.into(),
+                })
+        }
+    }
+}
@@ -1,104 +1,198 @@
 use {
-    crate::play::Queue,
-    bandcamp_api::data::releases::{Release, Track},
+    crate::{
+        local::LocalFiles,
+        play::{Queue, QueuedTrack},
+    },
+    bandcamp_api::data::{common::Id, releases::Release},
     fetch::Fetcher,
     input::binds::Bindings,
-    play::Player,
-    std::cell::Cell,
+    play::{Crossfade, Player},
+    std::{cell::RefCell, time::Duration},
+    url::Url,
 };
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
 pub enum Focus {
     Release,
+    Lyrics,
     Search,
     #[default]
     NavBody,
 }
 
-pub type Stream = stream::AudioStream<Box<bc_track::TrackStream>>;
+pub type Stream = stream::AudioStream<Box<bc_track::Source>>;
 pub type Audio = mp3::Mp3<Stream>;
 
 #[derive(Default)]
 pub struct Next {
     track: Option<Audio>,
-    pending: Cell<bool>,
+    /// Whether the track begins with silence.
+    starts_silent: bool,
+    pending: RefCell<Option<Url>>,
+    /// Whether the track has been handed to the player
+    /// to follow the current one.
+    queued: bool,
 }
 
 impl Next {
-    fn needed(&self) -> bool {
-        !self.pending.get() && self.track.is_none()
+    pub(super) fn needed(&self) -> bool {
+        self.pending.borrow().is_none() && self.track.is_none() && !self.queued
     }
 
-    pub fn set(&mut self, track: Audio) {
+    fn expect(&self, url: Url) {
+        self.pending.replace(url.into());
+    }
+
+    /// Returns whether the track at the provided URL is the
+    /// one that's currently being fetched.
+    pub fn expects(&self, url: &Url) -> bool {
+        self.pending.borrow().as_ref() == Some(url)
+    }
+
+    pub fn set(&mut self, track: Audio, starts_silent: bool) {
         self.track.replace(track);
-        self.pending.set(false);
+        self.starts_silent = starts_silent;
+        self.pending.replace(None);
+    }
+
+    /// Stops waiting for the pending track, e.g. because fetching it failed.
+    pub fn abandon(&self) {
+        self.pending.replace(None);
     }
 
     fn clear(&mut self) {
         self.track = None;
-        self.pending.set(false);
+        self.queued = false;
+        self.pending.replace(None);
+    }
+
+    /// Takes the track to queue it in the player.
+    fn queue(&mut self) -> Option<Audio> {
+        let track = self.track.take()?;
+        self.queued = true;
+
+        Some(track)
     }
 
     pub fn take(&mut self) -> Option<Audio> {
         self.track.take().inspect(|_| {
-            self.pending.set(false);
+            self.pending.replace(None);
         })
     }
 }
 
+/// Where to pick up playback of the current track from a previous session.
+#[derive(Debug, Clone, Copy)]
+pub struct Resume {
+    pub elapsed: Duration,
+    /// Whether to stay paused once the track is loaded.
+    pub paused: bool,
+}
+
+/// A release that's being fetched to be displayed, rather than explored.
+#[derive(Debug, Clone, Copy)]
+pub struct Incoming {
+    pub id: Id<Release>,
+    /// The track to start playing once it arrives.
+    /// If there is none, it's only displayed.
+    pub play: Option<usize>,
+}
+
 pub struct Core {
     pub(super) bindings: Bindings,
     pub(super) fetcher: Fetcher,
     pub(super) focus: Focus,
     pub queue: Queue,
+    pub(super) local: LocalFiles,
     pub(super) next: Next,
     pub player: Player<Audio>,
     pub release: Option<Release>,
+    pub(super) incoming: Option<Incoming>,
+    pub(super) resume: Option<Resume>,
+    /// How long to crossfade between tracks for.
+    pub(super) crossfade: Duration,
 }
 
 impl Core {
     pub fn set_release(&mut self, release: Release, start_track: usize) {
+        self.incoming = None;
+        self.resume = None;
         self.player.stop();
         self.next.clear();
-        self.queue.clone_tracks(&release.tracks);
-        self.queue.set_track(start_track);
-        if let Some(track) = self.queue.current() {
-            self.fetch_track(track)
-        }
+        self.queue.set_release(&release, start_track);
+        self.fetch_current();
         self.release.replace(release);
     }
 
-    pub fn play(&mut self, track: usize) {
+    /// Plays the track at the provided position in the queue.
+    pub fn play(&mut self, index: usize) {
+        self.resume = None;
         self.player.stop();
-        self.queue.set_track(track);
-        if let Some(track) = self.queue.current() {
-            self.fetch_track(track)
-        }
+        self.next.clear();
+        self.queue.set_track(index);
+        self.fetch_current();
     }
 
     pub fn step_track(&mut self, op: impl Fn(&mut Queue)) -> Option<usize> {
         op(&mut self.queue);
 
-        let track = match self.queue.current() {
-            Some(track) => Some(track),
-            None => {
-                self.queue.set_track(0);
-                self.queue.current()
-            }
-        }?;
+        if self.queue.current().is_none() {
+            self.queue.set_track(0);
+        }
+
+        let track = self.queue.current()?;
 
+        self.resume = None;
         self.player.stop();
+        self.next.clear();
         self.fetch_track(track);
-        self.queue.index().into()
+        self.playing_in_release()
+    }
+
+    /// Returns the position of the current track within the
+    /// displayed release, if it's from that release.
+    pub fn playing_in_release(&self) -> Option<usize> {
+        self.queue.playing_in(self.release.as_ref()?)
+    }
+
+    pub(super) fn fetch_current(&self) {
+        if let Some(track) = self.queue.current() {
+            self.fetch_track(track)
+        }
     }
 
-    pub fn fetch_track(&self, track: &Track) {
-        self.next.pending.set(true);
-        self.fetcher.fetch_track(track.stream.mp3_128.clone())
+    /// Starts fetching the provided track, or opening
+    /// its local copy, if there is one.
+    pub fn fetch_track(&self, track: &QueuedTrack) {
+        let stream = match track.track.streamable() {
+            Some(stream) => stream,
+            None => return,
+        };
+
+        let url = stream.mp3_128.clone();
+
+        self.next.expect(url.clone());
+
+        match self.local.find(track) {
+            Some(path) => self.fetcher.open_track(url, path.to_owned()),
+            None => self.fetcher.fetch_track(stream, track.release_args()),
+        }
     }
 
+    /// Toggles playback and returns whether the player is playing afterwards.
+    /// Starts fetching the current track if nothing has been played yet.
     pub fn toggle_play(&mut self) -> bool {
-        if self.player.is_paused() {
+        if self.player.is_empty() {
+            if self.next.needed() {
+                self.fetch_current()
+            }
+
+            if let Some(resume) = &mut self.resume {
+                resume.paused = false
+            }
+
+            self.queue.current().is_some()
+        } else if self.player.is_paused() {
             self.player.resume();
             true
         } else {
@@ -107,11 +201,112 @@ impl Core {
         }
     }
 
+    /// Resets the prefetched track after the tracks following the
+    /// current one have changed.
+    pub fn invalidate_next(&mut self) {
+        if !self.player.is_empty() {
+            self.next.clear();
+            self.player.dequeue()
+        }
+    }
+
+    /// Hands the fetched next track to the player, so it
+    /// plays right after the current one without a gap.
+    pub(super) fn queue_next(&mut self) -> Result<(), play::Error> {
+        if self.player.is_empty() {
+            return Ok(())
+        }
+
+        match self.next.queue() {
+            Some(audio) => {
+                let crossfade = self.next_crossfade(self.next.starts_silent);
+                self.player.enqueue(audio, crossfade)
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Returns how to fade into the next track, unless it carries on
+    /// from the current one without a gap, which fading would cut into.
+    /// That's the case if it directly follows the current one on its
+    /// release and doesn't start with silence.
+    fn next_crossfade(&self, starts_silent: bool) -> Option<Crossfade> {
+        if self.crossfade == Duration::ZERO {
+            return None
+        }
+
+        let current = self.queue.current()?;
+        let next = self.queue.next()?;
+
+        if next.release == current.release && next.number == current.number + 1 && !starts_silent {
+            return None
+        }
+
+        let len = self.crossfade.min(current.track.duration);
+
+        Some(Crossfade {
+            at: current.track.duration - len,
+            len,
+        })
+    }
+
+    /// Moves the queue on to the next track if the player has
+    /// started playing it, returning whether it did.
+    pub(super) fn follow_player(&mut self) -> bool {
+        let advanced = self.player.advanced();
+
+        if advanced {
+            self.next.queued = false;
+            self.queue.advance()
+        }
+
+        advanced
+    }
+
     pub fn maybe_fetch_next(&self) {
         if self.next.needed() {
-            if let Some(track) = self.queue.prepare_next(self.player.elapsed()) {
+            if let Some(track) = self
+                .queue
+                .prepare_next(self.player.elapsed(), self.crossfade)
+            {
                 self.fetch_track(track)
             }
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use {
+        super::*,
+        crate::{play::test::release, state::test::state},
+    };
+
+    #[test]
+    fn next_crossfade() {
+        let mut queue = Queue::default();
+        queue.set_release(&release(1, 3), 0);
+        queue.append(&release(2, 1), 0);
+
+        let mut state = state("next_crossfade", queue);
+        let core = &mut state.core;
+        core.crossfade = Duration::from_secs(5);
+
+        let fade = Some(Crossfade {
+            at: Duration::from_secs(55),
+            len: Duration::from_secs(5),
+        });
+
+        // the next track on the release carries on from the current one
+        assert_eq!(core.next_crossfade(false), None);
+        // unless there's silence in between
+        assert_eq!(core.next_crossfade(true), fade);
+
+        // tracks from elsewhere fade in either way
+        core.queue.set_track(2);
+        assert_eq!(core.next_crossfade(false), fade);
+
+        core.crossfade = Duration::ZERO;
+        assert_eq!(core.next_crossfade(true), None);
+    }
+}
@@ -0,0 +1,37 @@
+use {
+    crate::state::{explore::ExploreState, Core, WidgetState},
+    bandcamp_api::data::releases::{Release, ReleaseArgs},
+};
+
+impl super::Explore for super::Discover {
+    fn selection_down(&self, widgets: &mut WidgetState) {
+        let len = self.items.len() + self.next_page().is_some() as usize;
+
+        if super::can_select_down(widgets.nav.selected(), len) {
+            widgets.nav.selection_down()
+        }
+    }
+
+    fn confirm(&self, core: &mut Core, widgets: &mut WidgetState) -> Option<ExploreState> {
+        widgets.nav.selected().and_then(|idx| {
+            if let Some(discovered) = self.items.get(idx) {
+                let args = ReleaseArgs {
+                    id: discovered.id,
+                    kind: discovered.kind,
+                    outlet: discovered.artist_id,
+                };
+
+                core.fetcher.query::<Release, _>(&args);
+
+                Some(ExploreState::loading())
+            } else {
+                if let Some(args) = self.next_page() {
+                    core.fetcher.discover(args);
+                    widgets.nav.discover().set_loading(true);
+                }
+
+                None
+            }
+        })
+    }
+}
@@ -1,3 +1,4 @@
+mod discover;
 mod empty;
 mod fan;
 mod outlet;
@@ -8,7 +9,7 @@ pub use empty::*;
 
 use super::*;
 
-#[enumerate::enumerate(ExploreState: Blank, Loading, Fan, Search, Outlet, Release)]
+#[enumerate::enumerate(ExploreState: Blank, Loading, Fan, Search, Discover, Outlet, Release)]
 pub trait Explore {
     fn selection_down(&self, widgets: &mut WidgetState);
 
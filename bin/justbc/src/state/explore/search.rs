@@ -1,41 +1,48 @@
 use {
     crate::state::{explore::ExploreState, Core, WidgetState},
     bandcamp_api::data::{
+        common::Id,
         fans::{Fan, FanArgs},
         outlets::Outlet,
         releases::{Release, ReleaseArgs, ReleaseKind},
         search::SearchResult,
     },
+    url::Url,
 };
 
 impl super::Explore for super::Search {
     fn selection_down(&self, widgets: &mut WidgetState) {
-        if super::can_select_down(widgets.nav.selected(), self.results.len()) {
+        let len = self.results.len() + self.more as usize;
+
+        if super::can_select_down(widgets.nav.selected(), len) {
             widgets.nav.selection_down()
         }
     }
 
     fn confirm(&self, core: &mut Core, widgets: &mut WidgetState) -> Option<ExploreState> {
-        widgets.nav.selected().map(|idx| {
-            match &self.results[idx] {
-                SearchResult::Outlet(o) => core.fetcher.query::<Outlet, _>(&o.id),
-                SearchResult::Album(a) => {
-                    let args = ReleaseArgs {
-                        id: a.id,
-                        kind: ReleaseKind::Album,
-                        outlet: a.artist_id,
-                    };
+        widgets.nav.selected().and_then(|idx| {
+            let result = match self.results.get(idx) {
+                Some(result) => result,
+                None => {
+                    if let Some(args) = self.next_page() {
+                        core.fetcher.search(args);
+                        widgets.nav.results().set_loading(true);
+                    }
 
-                    core.fetcher.query::<Release, _>(&args)
+                    return None
                 }
-                SearchResult::Track(t) => {
-                    let args = ReleaseArgs {
-                        id: t.id,
-                        kind: ReleaseKind::Track,
-                        outlet: t.artist_id,
-                    };
+            };
 
-                    core.fetcher.query::<Release, _>(&args)
+            let fetching = match result {
+                SearchResult::Outlet(o) => {
+                    core.fetcher.query::<Outlet, _>(&o.id);
+                    true
+                }
+                SearchResult::Album(a) => {
+                    release(core, a.id, ReleaseKind::Album, a.artist_id, a.url.as_ref())
+                }
+                SearchResult::Track(t) => {
+                    release(core, t.id, ReleaseKind::Track, t.artist_id, t.url.as_ref())
                 }
                 SearchResult::Fan(f) => {
                     let args = FanArgs {
@@ -44,11 +51,33 @@ impl super::Explore for super::Search {
                         count: FanArgs::DEFAULT_COUNT,
                     };
 
-                    core.fetcher.query::<Fan, _>(&args)
+                    core.fetcher.query::<Fan, _>(&args);
+                    true
                 }
             };
 
-            ExploreState::loading()
+            fetching.then(ExploreState::loading)
         })
     }
 }
+
+/// Starts fetching a release from the search results. Full search results
+/// don't include the artist's id, so their page has to be resolved instead.
+/// Returns whether there was enough to go on to fetch it.
+fn release(
+    core: &Core,
+    id: Id<Release>,
+    kind: ReleaseKind,
+    outlet: Option<Id<Outlet>>,
+    url: Option<&Url>,
+) -> bool {
+    match (outlet, url) {
+        (Some(outlet), _) => core
+            .fetcher
+            .query::<Release, _>(&ReleaseArgs { id, kind, outlet }),
+        (None, Some(url)) => core.fetcher.resolve(url.clone()),
+        (None, None) => return false,
+    }
+
+    true
+}
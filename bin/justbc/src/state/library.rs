@@ -0,0 +1,100 @@
+use {
+    crate::state::{can_select_down, can_select_up, Core, ExploreState, WidgetState},
+    bandcamp_api::data::{fans::Fan, outlets::Outlet, releases::Release},
+    fetch::Fetcher,
+    library::{Entry, Library},
+    std::path::PathBuf,
+};
+
+/// The saved library along with the file it's stored in
+/// and the filter currently applied to it.
+pub struct LibraryState {
+    pub library: Library,
+    pub filter: String,
+    file: PathBuf,
+}
+
+impl LibraryState {
+    pub fn load(file: PathBuf) -> library::Result<LibraryState> {
+        Ok(LibraryState {
+            library: Library::load(&file)?,
+            filter: String::new(),
+            file,
+        })
+    }
+
+    fn visible(&self) -> impl Iterator<Item = &Entry> {
+        self.library.filtered(&self.filter)
+    }
+
+    fn selected(&self, widgets: &WidgetState) -> Option<&Entry> {
+        self.visible().nth(widgets.nav.selected()?)
+    }
+
+    pub fn selection_down(&self, widgets: &mut WidgetState) {
+        if can_select_down(widgets.nav.selected(), self.visible().count()) {
+            widgets.nav.selection_down()
+        }
+    }
+
+    pub fn selection_up(&self, widgets: &mut WidgetState) {
+        if can_select_up(widgets.nav.selected()) {
+            widgets.nav.selection_up()
+        }
+    }
+
+    pub fn confirm(&self, core: &mut Core, widgets: &mut WidgetState) -> Option<ExploreState> {
+        Some(explore(self.selected(widgets)?, &core.fetcher))
+    }
+
+    pub fn set_filter(&mut self, filter: &str, widgets: &mut WidgetState) {
+        if self.filter != filter {
+            self.filter = filter.into();
+            widgets.nav.library().select(self.first());
+        }
+    }
+
+    fn first(&self) -> Option<usize> {
+        self.visible().next().map(|_| 0)
+    }
+
+    /// Saves an entry to the library and writes it to disk.
+    pub fn add(&mut self, entry: Entry) -> library::Result {
+        if self.library.add(entry) {
+            self.library.save(&self.file)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Removes the selected entry from the library and writes it to disk.
+    pub fn remove_selected(&mut self, widgets: &mut WidgetState) -> library::Result {
+        let entry = match self.selected(widgets) {
+            Some(entry) => entry.clone(),
+            None => return Ok(()),
+        };
+
+        self.library.remove(&entry);
+
+        let selected = widgets
+            .nav
+            .selected()
+            .filter(|idx| *idx < self.visible().count())
+            .or_else(|| self.visible().count().checked_sub(1));
+
+        widgets.nav.library().select(selected);
+
+        self.library.save(&self.file)
+    }
+}
+
+/// Starts fetching the provided entry in order to explore it.
+pub fn explore(entry: &Entry, fetcher: &Fetcher) -> ExploreState {
+    match entry {
+        Entry::Release(r) => fetcher.query::<Release, _>(&r.args()),
+        Entry::Outlet(o) => fetcher.query::<Outlet, _>(&o.id),
+        Entry::Fan(f) => fetcher.query::<Fan, _>(&f.args()),
+    }
+
+    ExploreState::loading()
+}
@@ -1,23 +1,40 @@
+mod art;
 mod core;
 mod explore;
+mod library;
 
-pub use {self::core::Core, explore::ExploreState};
+pub use {self::core::Core, art::ArtCache, explore::ExploreState, library::LibraryState};
 
 use {
-    self::core::{Audio, Focus, Stream},
+    self::core::{Audio, Focus, Incoming, Resume, Stream},
     crate::{
         cfg::{self, StateConfig},
+        local::LocalFiles,
         play::Queue,
+        session::Session,
+    },
+    ::library::{Entry, SavedRelease},
+    bandcamp_api::{
+        data::{
+            common::{Art, Id},
+            discover::{Discover, DiscoverArgs},
+            fans::{Fan, FanArgs},
+            outlets::Outlet,
+            releases::{Lyrics, Release, ReleaseArgs},
+            search::{Search, SearchArgs, SearchFilter},
+        },
+        resolve::{self, Page, Resolved},
     },
-    bandcamp_api::data::{fans::Fan, outlets::Outlet, releases::Release, search::Search},
     bc_tui::{nav::NavViewState, releases::ReleaseViewState, tracks::PlayBarState},
+    directories::UserDirs,
     explore::Explore,
-    fetch::Fetcher,
+    fetch::{download::Download, DownloadStatus, Fetcher, Progress},
     gen_tui::widgets::input::Message as InputMessage,
     input::binds::Bindings,
-    media_keys::MediaKey,
+    media_keys::{MediaKey, Metadata, Playback, Status},
     play::Player,
-    std::{error::Error, ops::Add, time::Duration},
+    std::{collections::BTreeMap, error::Error, ops::Add, path::PathBuf, time::Duration},
+    url::Url,
 };
 
 #[derive(Copy, Clone, Eq, PartialEq, Default)]
@@ -25,24 +42,24 @@ pub enum Active {
     Library,
     #[default]
     Explore,
+    Queue,
 }
 
 impl Active {
     fn cycle(&mut self) {
-        *self = if *self == Active::Library {
-            Active::Explore
-        } else {
-            Active::Library
+        *self = match self {
+            Active::Library => Active::Explore,
+            Active::Explore => Active::Queue,
+            Active::Queue => Active::Library,
         }
     }
 }
 
-#[derive(Default)]
 pub struct Navigation {
-    #[allow(unused)]
-    pub library: (),
+    pub library: LibraryState,
     pub explore: ExploreState,
     pub active: Active,
+    pub search_filter: SearchFilter,
 }
 
 #[derive(Default)]
@@ -51,17 +68,47 @@ pub struct WidgetState {
     pub play_bar: PlayBarState,
     pub release: ReleaseViewState,
     pub release_scroll: u16,
+    pub lyrics: LyricsState,
+}
+
+/// The pane showing the lyrics of the playing track.
+#[derive(Default)]
+pub struct LyricsState {
+    pub shown: bool,
+    pub scroll: u16,
+    /// The release and position of the track whose lyrics were shown last.
+    track: Option<(Id<Release>, usize)>,
+}
+
+impl LyricsState {
+    /// Scrolls back to the top once another track than the last one plays.
+    pub fn follow(&mut self, track: Option<(Id<Release>, usize)>) {
+        if self.track != track {
+            self.track = track;
+            self.scroll = 0
+        }
+    }
 }
 
 pub struct State {
     pub core: Core,
     pub navigation: Navigation,
     pub widgets: WidgetState,
+    /// The batches of tracks being downloaded, with their latest progress.
+    pub downloads: BTreeMap<usize, Option<Progress>>,
+    pub art: ArtCache,
+    download_dir: Option<PathBuf>,
     pub error: Option<Box<dyn Error>>,
 }
 
 impl State {
-    pub fn new(cfg: StateConfig, fetcher: Fetcher) -> State {
+    pub fn new(
+        cfg: StateConfig,
+        library: LibraryState,
+        queue: Queue,
+        local: LocalFiles,
+        fetcher: Fetcher,
+    ) -> State {
         let mut player = Player::new();
         player.set_volume(cfg.general.volume);
 
@@ -72,17 +119,82 @@ impl State {
                 bindings,
                 fetcher,
                 focus: <_>::default(),
-                queue: <_>::default(),
+                queue,
+                local,
                 next: <_>::default(),
                 player,
                 release: None,
+                incoming: None,
+                resume: None,
+                crossfade: Duration::from_secs_f32(cfg.general.crossfade.max(0.)),
+            },
+            navigation: Navigation {
+                library,
+                explore: <_>::default(),
+                active: <_>::default(),
+                search_filter: <_>::default(),
             },
-            navigation: <_>::default(),
             widgets: <_>::default(),
+            downloads: <_>::default(),
+            art: <_>::default(),
+            download_dir: cfg.general.download_dir,
             error: None,
         }
     }
 
+    /// Picks up where the provided session left off.
+    /// The current track of the queue is loaded, but stays paused.
+    pub fn restore(&mut self, session: Session) {
+        if let Some(release) = session.release {
+            self.core.fetcher.query::<Release, _>(&release.args());
+            self.core.incoming = Some(Incoming {
+                id: release.id,
+                play: None,
+            });
+        } else {
+            self.core.fetch_current()
+        }
+
+        if self.core.queue.current().is_some() {
+            self.core.resume = Some(Resume {
+                elapsed: Duration::from_secs_f64(session.elapsed.max(0.)),
+                paused: true,
+            })
+        }
+
+        if let Some(query) = session.search {
+            query
+                .chars()
+                .for_each(|ch| self.widgets.nav.input.insert(ch));
+            self.search(query);
+        } else if let Some(entry) = session.explore {
+            self.navigation.explore = library::explore(&entry, &self.core.fetcher);
+        }
+    }
+
+    /// Returns the current session, so it can be restored later.
+    pub fn session(&self) -> Session {
+        let elapsed = match self.core.resume {
+            Some(resume) if self.core.player.is_empty() => resume.elapsed,
+            _ => self.core.player.elapsed(),
+        };
+
+        let (search, explore) = match &self.navigation.explore {
+            ExploreState::Search(_) => (Some(self.widgets.nav.input.text().into()), None),
+            ExploreState::Release(r) => (None, Some(r.into())),
+            ExploreState::Outlet(o) => (None, Some(o.into())),
+            ExploreState::Fan(f) => (None, Some(f.into())),
+            _ => (None, None),
+        };
+
+        Session {
+            elapsed: elapsed.as_secs_f64(),
+            search,
+            release: self.core.release.as_ref().map(SavedRelease::from),
+            explore,
+        }
+    }
+
     pub fn input(&mut self, key: input::Key) {
         self.error = None;
 
@@ -100,10 +212,18 @@ impl State {
             FocusRelease => self.focus(Focus::Release),
             FocusNav => self.focus(Focus::NavBody),
             FocusSearchBar => self.focus(Focus::Search),
+            TabLibrary => {
+                self.navigation.active = Active::Library;
+                self.focus(Focus::NavBody)
+            }
             TabExplore => {
                 self.navigation.active = Active::Explore;
                 self.focus(Focus::NavBody)
             }
+            TabQueue => {
+                self.navigation.active = Active::Queue;
+                self.focus(Focus::NavBody)
+            }
             CycleTabs => self.navigation.active.cycle(),
             SelectionDown => self.selection_down(),
             SelectionUp => self.selection_up(),
@@ -112,12 +232,81 @@ impl State {
             Confirm => self.confirm(),
             VolumeUp => self.update_volume(Self::VOL_STEP),
             VolumeDown => self.update_volume(-Self::VOL_STEP),
-            NextTrack => self.step_track(Queue::advance),
+            NextTrack => self.step_track(Queue::skip),
             PrevTrack => self.step_track(Queue::regress),
             TogglePlay => self.toggle_play(),
             SkipAhead => self.seek(<_>::add),
             SkipBack => self.seek(saturating_sub),
-            _ => {}
+            SaveToLibrary => self.save_to_library(),
+            RemoveFromLibrary => self.remove_from_library(),
+            AppendToQueue => self.enqueue(Queue::append),
+            PlayNext => self.enqueue(Queue::play_next),
+            RemoveFromQueue => self.remove_from_queue(),
+            ToggleShuffle => self.change_mode(Queue::toggle_shuffle),
+            CycleRepeat => self.change_mode(Queue::cycle_repeat),
+            DownloadTrack => self.download_track(),
+            DownloadRelease => self.download_release(),
+            CycleSearchFilter => self.cycle_search_filter(),
+            CycleDiscoverSort => self.cycle_discover_sort(),
+            ToggleLyrics => self.toggle_lyrics(),
+        }
+    }
+
+    fn toggle_lyrics(&mut self) {
+        let lyrics = &mut self.widgets.lyrics;
+        lyrics.shown = !lyrics.shown;
+
+        if lyrics.shown {
+            self.focus(Focus::Lyrics)
+        } else if self.core.focus == Focus::Lyrics {
+            self.focus(Focus::Release)
+        }
+    }
+
+    /// Shows the discovered releases in another order.
+    fn cycle_discover_sort(&mut self) {
+        if self.navigation.active != Active::Explore {
+            return
+        }
+
+        if let ExploreState::Discover(Discover {
+            args: Some(args), ..
+        }) = &self.navigation.explore
+        {
+            let args = DiscoverArgs {
+                sort: args.sort.cycle(),
+                page: 0,
+                ..args.clone()
+            };
+
+            self.core.fetcher.discover(args);
+            self.navigation.explore = ExploreState::loading();
+        }
+    }
+
+    /// Starts a full search with the current filter.
+    fn search(&mut self, query: impl Into<String>) {
+        let args = SearchArgs::new(query, self.navigation.search_filter);
+        self.core.fetcher.search(args);
+        self.navigation.explore = ExploreState::loading();
+    }
+
+    /// Switches to searching for something else,
+    /// repeating the search that's shown, if there is one.
+    fn cycle_search_filter(&mut self) {
+        let filter = &mut self.navigation.search_filter;
+        *filter = filter.cycle();
+
+        if self.navigation.active != Active::Explore {
+            return
+        }
+
+        if let ExploreState::Search(Search {
+            args: Some(args), ..
+        }) = &self.navigation.explore
+        {
+            let query = args.query.clone();
+            self.search(query)
         }
     }
 
@@ -139,7 +328,12 @@ impl State {
         match self.core.focus {
             Focus::NavBody => match self.navigation.active {
                 Active::Explore => self.navigation.explore.selection_down(&mut self.widgets),
-                Active::Library => {}
+                Active::Library => self.navigation.library.selection_down(&mut self.widgets),
+                Active::Queue => {
+                    if can_select_down(self.widgets.nav.selected(), self.core.queue.len()) {
+                        self.widgets.nav.selection_down()
+                    }
+                }
             },
             Focus::Release => {
                 if let Some(rel) = self.core.release.as_ref() {
@@ -148,6 +342,7 @@ impl State {
                     }
                 }
             }
+            Focus::Lyrics => self.scroll_down(),
             _ => {}
         }
     }
@@ -156,39 +351,46 @@ impl State {
         match self.core.focus {
             Focus::NavBody => match self.navigation.active {
                 Active::Explore => self.navigation.explore.selection_up(&mut self.widgets),
-                Active::Library => {}
-            },
-            Focus::Release => {
-                if self.core.release.is_some() && can_select_up(self.widgets.release.selected()) {
-                    self.widgets.release.selection_up()
+                Active::Library => self.navigation.library.selection_up(&mut self.widgets),
+                Active::Queue => {
+                    if can_select_up(self.widgets.nav.selected()) {
+                        self.widgets.nav.selection_up()
+                    }
                 }
+            },
+            Focus::Release
+                if self.core.release.is_some()
+                    && can_select_up(self.widgets.release.selected()) =>
+            {
+                self.widgets.release.selection_up()
             }
+            Focus::Lyrics => self.scroll_up(),
             _ => {}
         }
     }
 
     fn scroll_down(&mut self) {
         match self.core.focus {
-            Focus::NavBody => match self.navigation.active {
-                Active::Explore => self.widgets.nav.scroll_down(),
-                Active::Library => {}
-            },
+            Focus::NavBody => self.widgets.nav.scroll_down(),
             Focus::Release => {
                 self.widgets.release_scroll = self.widgets.release_scroll.saturating_add(1)
             }
+            Focus::Lyrics => {
+                self.widgets.lyrics.scroll = self.widgets.lyrics.scroll.saturating_add(1)
+            }
             _ => {}
         }
     }
 
     fn scroll_up(&mut self) {
         match self.core.focus {
-            Focus::NavBody => match self.navigation.active {
-                Active::Explore => self.widgets.nav.scroll_up(),
-                Active::Library => {}
-            },
+            Focus::NavBody => self.widgets.nav.scroll_up(),
             Focus::Release => {
                 self.widgets.release_scroll = self.widgets.release_scroll.saturating_sub(1)
             }
+            Focus::Lyrics => {
+                self.widgets.lyrics.scroll = self.widgets.lyrics.scroll.saturating_sub(1)
+            }
             _ => {}
         }
     }
@@ -205,14 +407,34 @@ impl State {
                         self.navigation.explore = xp;
                     }
                 }
-                Active::Library => {}
+                Active::Library => {
+                    if let Some(xp) = self
+                        .navigation
+                        .library
+                        .confirm(&mut self.core, &mut self.widgets)
+                    {
+                        self.navigation.explore = xp;
+                        self.navigation.active = Active::Explore;
+                    }
+                }
+                Active::Queue => {
+                    if let Some(idx) = self.widgets.nav.selected() {
+                        if self.core.queue.index() == idx && !self.core.player.is_empty() {
+                            self.toggle_play()
+                        } else {
+                            self.core.play(idx);
+                            self.widgets.release.play(self.core.playing_in_release())
+                        }
+                    }
+                }
             },
             Focus::Release => {
                 if let Some(track) = self.widgets.release.selected() {
                     if self.widgets.release.playing() == Some(track) {
                         self.toggle_play()
-                    } else {
-                        self.core.play(track)
+                    } else if let Some(release) = self.core.release.clone() {
+                        self.core.set_release(release, track);
+                        self.widgets.release.play(track)
                     }
                 }
             }
@@ -232,30 +454,203 @@ impl State {
                 InputMessage::Cancel => self.focus(Focus::NavBody),
                 InputMessage::Confirm => match self.navigation.active {
                     Active::Explore => {
-                        self.core.fetcher.query::<Search, _>(input.text());
-                        self.navigation.explore = ExploreState::loading();
+                        let text = input.text();
+
+                        if let Some(url) = bandcamp_url(text) {
+                            self.core.fetcher.resolve(url);
+                            self.navigation.explore = ExploreState::loading();
+                        } else if let Some(args) = discover_tag(text) {
+                            self.core.fetcher.discover(args);
+                            self.navigation.explore = ExploreState::loading();
+                        } else {
+                            let query = text.to_owned();
+                            self.search(query)
+                        }
+
                         self.focus(Focus::NavBody)
                     }
-                    Active::Library => {}
+                    Active::Library | Active::Queue => self.focus(Focus::NavBody),
                 },
             }
         }
+
+        if self.navigation.active == Active::Library {
+            let filter = self.widgets.nav.input.text().to_owned();
+            self.navigation
+                .library
+                .set_filter(&filter, &mut self.widgets)
+        }
+    }
+
+    fn save_to_library(&mut self) {
+        let entry = match (self.core.focus, self.navigation.active) {
+            (Focus::Release, _) => self.core.release.as_ref().map(Entry::from),
+            (_, Active::Explore) => match &self.navigation.explore {
+                ExploreState::Release(r) => Some(r.into()),
+                ExploreState::Outlet(o) => Some(o.into()),
+                ExploreState::Fan(f) => Some(f.into()),
+                _ => None,
+            },
+            _ => None,
+        };
+
+        if let Some(entry) = entry {
+            self.try_do(|this| this.navigation.library.add(entry).map_err(<_>::into));
+        }
+    }
+
+    fn remove_from_library(&mut self) {
+        if self.core.focus == Focus::NavBody && self.navigation.active == Active::Library {
+            self.try_do(|this| {
+                this.navigation
+                    .library
+                    .remove_selected(&mut this.widgets)
+                    .map_err(<_>::into)
+            });
+        }
+    }
+
+    /// Returns the selected track, either in the displayed release or in
+    /// the release being explored.
+    fn selected_track(&self) -> Option<(&Release, usize)> {
+        match (self.core.focus, self.navigation.active) {
+            (Focus::Release, _) => self
+                .core
+                .release
+                .as_ref()
+                .zip(self.widgets.release.selected()),
+            (Focus::NavBody, Active::Explore) => match &self.navigation.explore {
+                ExploreState::Release(r) => self.widgets.nav.selected().map(|idx| (r, idx)),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Adds the selected track to the queue using the provided operation.
+    fn enqueue(&mut self, op: impl Fn(&mut Queue, &Release, usize)) {
+        let selected = self
+            .selected_track()
+            .map(|(release, track)| (release.clone(), track));
+
+        if let Some((release, track)) = selected {
+            op(&mut self.core.queue, &release, track);
+            self.core.invalidate_next()
+        }
+    }
+
+    fn download_track(&mut self) {
+        let selected = self
+            .selected_track()
+            .map(|(release, track)| (release.clone(), track));
+
+        if let Some((release, track)) = selected {
+            self.download(&release, Some(track))
+        }
+    }
+
+    fn download_release(&mut self) {
+        let release = match (self.core.focus, self.navigation.active) {
+            (Focus::Release, _) => self.core.release.clone(),
+            (_, Active::Explore) => match &self.navigation.explore {
+                ExploreState::Release(r) => Some(r.clone()),
+                _ => None,
+            },
+            _ => None,
+        };
+
+        if let Some(release) = release {
+            self.download(&release, 0..release.tracks.len())
+        }
+    }
+
+    fn download(&mut self, release: &Release, tracks: impl IntoIterator<Item = usize>) {
+        let dir = match self.download_dir.clone().or_else(default_download_dir) {
+            Some(dir) => dir,
+            None => {
+                self.error = Some("couldn't find a directory to download to".into());
+                return
+            }
+        };
+
+        let downloads = Download::from_release(release, tracks, &dir);
+        let batch = self.core.fetcher.download(downloads);
+        self.downloads.insert(batch, None);
+    }
+
+    fn change_mode(&mut self, op: impl Fn(&mut Queue)) {
+        op(&mut self.core.queue);
+        self.core.invalidate_next()
+    }
+
+    fn remove_from_queue(&mut self) {
+        if self.core.focus != Focus::NavBody || self.navigation.active != Active::Queue {
+            return
+        }
+
+        let removed = self
+            .widgets
+            .nav
+            .selected()
+            .map(|idx| self.core.queue.remove(idx))
+            .unwrap_or_default();
+
+        if removed {
+            self.core.invalidate_next();
+
+            let selected = self
+                .widgets
+                .nav
+                .selected()
+                .filter(|idx| *idx < self.core.queue.len())
+                .or_else(|| self.core.queue.len().checked_sub(1));
+
+            self.widgets.nav.queue().select(selected);
+            self.widgets.release.play(self.core.playing_in_release())
+        }
+    }
+
+    /// Whether to fetch the lyrics missing from the provided release along
+    /// with it, which is only worth it if they can be shown, i.e. if its
+    /// tracks are queued or about to play, or the lyrics pane is open.
+    fn wants_lyrics(&self, release: &Release) -> bool {
+        let id = release.info.id;
+
+        release.lacks_lyrics()
+            && (self.widgets.lyrics.shown
+                || self
+                    .core
+                    .incoming
+                    .is_some_and(|incoming| incoming.id == id && incoming.play.is_some())
+                || self
+                    .core
+                    .queue
+                    .tracks()
+                    .iter()
+                    .any(|track| track.release == id))
     }
 
     pub fn set_response(&mut self, response: fetch::Response) {
         const FIRST: Option<usize> = Some(0);
 
-        match response {
-            fetch::Response::Search(s) => {
-                if self.try_set_explore(s, ExploreState::Search) {
-                    self.widgets.nav.results().select(FIRST)
-                }
+        if let fetch::Response::Release(Ok(release)) = &response {
+            if self.wants_lyrics(release) {
+                self.core.fetcher.query::<Lyrics, _>(&release.args())
             }
+        }
+
+        match response {
+            fetch::Response::Search(s) => self.receive_search(s),
             fetch::Response::Outlet(o) => {
                 if self.try_set_explore(o, ExploreState::Outlet) {
                     self.widgets.nav.outlet().select(FIRST)
                 }
             }
+            fetch::Response::Release(Ok(r))
+                if self.core.incoming.map(|i| i.id) == Some(r.info.id) =>
+            {
+                self.receive_release(r)
+            }
             fetch::Response::Release(r) => {
                 if self.try_set_explore(r, ExploreState::Release) {
                     self.widgets.nav.release().select(FIRST)
@@ -283,15 +678,218 @@ impl State {
                     }
                 });
             }
-            fetch::Response::Track(stream) => {
-                self.try_do(|this| {
-                    let _: () = this.core.next.set(Audio::new(Stream::new(stream?)?));
-                    Ok(())
+            fetch::Response::Discover(discover) => {
+                self.try_do(|this| match discover {
+                    Ok(mut discover) => {
+                        let continues = |existing: &Discover| match (&existing.args, &discover.args)
+                        {
+                            (Some(existing), Some(args)) => {
+                                args.page > 0 && args.continues(existing)
+                            }
+                            _ => false,
+                        };
+
+                        match &mut this.navigation.explore {
+                            ExploreState::Discover(existing) if continues(existing) => {
+                                existing.items.append(&mut discover.items);
+                                existing.args = discover.args;
+                                existing.total = discover.total;
+                                this.widgets.nav.discover().set_loading(false);
+                            }
+                            _ => {
+                                this.navigation.explore = ExploreState::Discover(discover);
+                                this.widgets.nav.discover().releases.select(FIRST);
+                            }
+                        }
+
+                        Ok(())
+                    }
+                    Err(e) => {
+                        if let ExploreState::Discover(_) = this.navigation.explore {
+                            this.widgets.nav.discover().set_loading(false)
+                        } else {
+                            this.navigation.explore = ExploreState::blank();
+                        }
+
+                        Err(e.into())
+                    }
+                });
+            }
+            fetch::Response::Resolved(resolved) => self.explore_resolved(resolved),
+            fetch::Response::Download(DownloadStatus::Progress(progress)) => {
+                self.downloads.insert(progress.batch, Some(progress));
+            }
+            fetch::Response::Download(DownloadStatus::Saved(download)) => {
+                self.core.local.add(&download);
+                self.try_do(|this| this.core.local.save());
+            }
+            fetch::Response::Download(DownloadStatus::Finished { batch, result }) => {
+                self.downloads.remove(&batch);
+                self.try_do(|_| result.map_err(<_>::into));
+            }
+            fetch::Response::Lyrics(lyrics) => {
+                // lyrics are an extra, so not getting them isn't worth an error
+                if let Ok(lyrics) = lyrics {
+                    self.core.queue.set_lyrics(&lyrics);
+
+                    if let Some(release) = &mut self.core.release {
+                        release.set_lyrics(&lyrics)
+                    }
+
+                    if let ExploreState::Release(release) = &mut self.navigation.explore {
+                        release.set_lyrics(&lyrics)
+                    }
+                }
+            }
+            // missing art isn't worth an error, the text is still there
+            fetch::Response::Art(id, cover) => {
+                if let Ok(cover) = cover {
+                    self.art.insert(id, cover)
+                }
+            }
+            fetch::Response::Updated(update) => self.receive_update(*update),
+            fetch::Response::Refreshed(release) => {
+                self.core.queue.update_release(&release);
+
+                if let Some(shown) = &mut self.core.release {
+                    if shown.info.id == release.info.id {
+                        *shown = release
+                    }
+                }
+            }
+            fetch::Response::Track(opened) => {
+                self.try_do(|this| match opened {
+                    Ok(opened) if this.core.next.expects(opened.source.url()) => {
+                        let audio = Audio::new(Stream::new(opened.source)?);
+                        let _: () = this.core.next.set(audio, opened.starts_silent);
+                        Ok(())
+                    }
+                    // a track that was fetched before the queue changed
+                    Ok(_) => Ok(()),
+                    Err(e) => {
+                        this.core.next.abandon();
+                        Err(e.into())
+                    }
                 });
             }
         }
     }
 
+    /// Replaces what was shown from the cache with its up to date version,
+    /// unless something else is shown by now.
+    fn receive_update(&mut self, update: fetch::Response) {
+        match (update, &mut self.navigation.explore) {
+            (fetch::Response::Release(Ok(release)), explore) => {
+                if let ExploreState::Release(existing) = explore {
+                    if existing.info.id == release.info.id {
+                        *existing = release.clone()
+                    }
+                }
+
+                self.set_response(fetch::Response::Refreshed(release))
+            }
+            (fetch::Response::Outlet(Ok(outlet)), ExploreState::Outlet(existing))
+                if existing.info.id == outlet.info.id =>
+            {
+                *existing = outlet
+            }
+            (fetch::Response::Fan(Ok(fan)), ExploreState::Fan(existing))
+                if existing.id == fan.id =>
+            {
+                // later pages of the collection may have been added already
+                if fan.collection.len() >= existing.collection.len() {
+                    *existing = fan
+                } else {
+                    existing.name = fan.name;
+                    existing.location = fan.location;
+                    existing.bio = fan.bio;
+                }
+            }
+            (lyrics @ fetch::Response::Lyrics(_), _) => self.set_response(lyrics),
+            _ => (),
+        }
+    }
+
+    fn receive_search(&mut self, search: bandcamp_api::Result<Search>) {
+        let existing = match &mut self.navigation.explore {
+            ExploreState::Search(existing) => existing,
+            _ => {
+                if self.try_set_explore(search, ExploreState::Search) {
+                    self.widgets.nav.results().select(Some(0))
+                }
+
+                return
+            }
+        };
+
+        let continues = |search: &Search| match (&existing.args, &search.args) {
+            (Some(existing), Some(args)) => args.page > 1 && args.continues(existing),
+            _ => false,
+        };
+
+        match search {
+            Ok(mut search) if continues(&search) => {
+                existing.results.append(&mut search.results);
+                existing.args = search.args;
+                existing.more = search.more;
+                self.widgets.nav.results().set_loading(false);
+            }
+            Ok(search) => {
+                self.navigation.explore = ExploreState::Search(search);
+                self.widgets.nav.results().select(Some(0));
+            }
+            // the results that are there already can still be used
+            Err(e) => {
+                self.widgets.nav.results().set_loading(false);
+                self.error.replace(e.into());
+            }
+        }
+    }
+
+    /// Starts fetching what a link pasted into the search bar points to.
+    fn explore_resolved(&mut self, resolved: resolve::Result<Resolved>) {
+        // the user has moved on while the link was being resolved
+        if !matches!(self.navigation.explore, ExploreState::Loading(_)) {
+            return
+        }
+
+        let fetcher = &self.core.fetcher;
+
+        match resolved {
+            Ok(Resolved::Outlet(id)) => fetcher.query::<Outlet, _>(&id),
+            Ok(Resolved::Release(args)) => fetcher.query::<Release, _>(&args),
+            Ok(Resolved::Fan(id)) => fetcher.query::<Fan, _>(&FanArgs {
+                id,
+                start: 0,
+                count: FanArgs::DEFAULT_COUNT,
+            }),
+            Err(e) => {
+                self.error.replace(e.into());
+                self.navigation.explore = ExploreState::blank();
+            }
+        }
+    }
+
+    fn receive_release(&mut self, release: Release) {
+        if let Some(track) = self.core.incoming.take().and_then(|i| i.play) {
+            self.widgets.release.play(track);
+            self.widgets.release.select(track.into());
+            self.core.set_release(release, track);
+            return
+        }
+
+        self.core.queue.update_release(&release);
+        self.core.release = Some(release);
+
+        let playing = self.core.playing_in_release();
+        self.widgets.release.play(playing);
+        self.widgets.release.select(playing);
+
+        if self.core.next.needed() {
+            self.core.fetch_current()
+        }
+    }
+
     fn try_set_explore<T, E, F>(&mut self, result: Result<T, E>, map: F) -> bool
     where
         E: Error + 'static,
@@ -354,36 +952,160 @@ impl State {
     const SEEK: Duration = Duration::from_secs(5);
 
     fn seek(&mut self, op: impl Fn(Duration, Duration) -> Duration) {
-        let new = op(self.core.player.elapsed(), Self::SEEK).min(
+        self.seek_to(op(self.core.player.elapsed(), Self::SEEK))
+    }
+
+    fn seek_to(&mut self, pos: Duration) {
+        let new = self.within_current(pos);
+
+        self.try_do(|this| this.core.player.seek(new).map_err(<_>::into));
+    }
+
+    /// Loads the track a previous session left off with at the position it
+    /// was at, before anything is played.
+    fn resume(&mut self, audio: Audio, resume: Resume) {
+        let pos = self.within_current(resume.elapsed);
+
+        self.try_do(|this| {
+            this.core
+                .player
+                .play_at(audio, pos, resume.paused)
+                .map_err(<_>::into)
+        });
+    }
+
+    /// Limits the provided position to the length of the current track.
+    fn within_current(&self, pos: Duration) -> Duration {
+        pos.min(
             self.core
                 .queue
                 .current()
-                .map(|track| track.duration)
+                .map(|track| track.track.duration)
                 .unwrap_or_default(),
-        );
-
-        self.try_do(|this| this.core.player.seek(new).map_err(<_>::into));
+        )
     }
 
     pub fn handle_media_key(&mut self, key: MediaKey) {
         match key {
             MediaKey::Stop => self.stop(),
             MediaKey::PlayPause => self.toggle_play(),
-            MediaKey::NextTrack => self.step_track(Queue::advance),
+            MediaKey::Play => {
+                if self.core.player.is_empty() || self.core.player.is_paused() {
+                    self.toggle_play()
+                }
+            }
+            MediaKey::Pause => self.core.player.pause(),
+            MediaKey::NextTrack => self.step_track(Queue::skip),
             MediaKey::PrevTrack => self.step_track(Queue::regress),
+            MediaKey::SeekForward(by) => self.seek_to(self.core.player.elapsed() + by),
+            MediaKey::SeekBack(by) => self.seek_to(saturating_sub(self.core.player.elapsed(), by)),
+            MediaKey::SetPosition(pos) => self.seek_to(pos),
         }
     }
 
-    fn finished_current_track(&self) -> bool {
-        self.core
-            .queue
-            .current()
-            .map(|track| self.core.player.passed(track.duration))
-            .unwrap_or_default()
+    /// Handles a request sent by a remote control.
+    pub fn handle_remote(&mut self, request: &remote::Request) -> remote::Response {
+        use remote::{Request, Response};
+
+        self.error = None;
+
+        match *request {
+            Request::Command { command } => self.handle_command(command),
+            Request::Play {
+                release,
+                outlet,
+                kind,
+                track,
+            } => {
+                let args = ReleaseArgs {
+                    id: Id::new(release),
+                    kind,
+                    outlet: Id::new(outlet),
+                };
+
+                self.core.fetcher.query::<Release, _>(&args);
+                self.core.incoming = Some(Incoming {
+                    id: args.id,
+                    play: Some(track),
+                });
+            }
+            Request::Seek { position } => {
+                if self.core.player.is_empty() {
+                    return Response::error("nothing is playing")
+                }
+
+                self.seek_to(position.0)
+            }
+            Request::Volume { volume } => self.core.player.set_volume(volume.clamp(0., 1.)),
+            Request::Status => return Response::Status(self.status()),
+        }
+
+        match &self.error {
+            Some(e) => Response::error(e.to_string()),
+            None => Response::Ok,
+        }
+    }
+
+    fn status(&self) -> remote::Status {
+        let queue = &self.core.queue;
+
+        remote::Status {
+            paused: self.core.player.is_empty() || self.core.player.is_paused(),
+            elapsed: remote::Position(self.core.player.elapsed()),
+            current: queue.current().map(|_| queue.index()),
+            queue: queue
+                .tracks()
+                .iter()
+                .map(|track| remote::Track {
+                    title: track.track.title.clone(),
+                    artist: track.artist.clone(),
+                    album: track.album.clone(),
+                    duration: remote::Position(track.track.duration),
+                })
+                .collect(),
+        }
+    }
+
+    /// Starts fetching the provided cover art, if there is any.
+    pub fn request_art(&mut self, id: Option<Id<Art>>) {
+        if let Some(id) = id {
+            self.art.request(id, &self.core.fetcher)
+        }
+    }
+
+    /// Returns what's currently playing, for the system to display.
+    pub fn playback(&self) -> Playback {
+        let player = &self.core.player;
+
+        let status = if player.is_empty() {
+            Status::Stopped
+        } else if player.is_paused() {
+            Status::Paused
+        } else {
+            Status::Playing
+        };
+
+        let track = self.core.queue.current().map(|track| Metadata {
+            release: track.release.get(),
+            number: track.number,
+            title: track.track.title.clone(),
+            artist: track.artist.clone(),
+            album: track.album.clone(),
+            length: track.track.duration,
+        });
+
+        Playback {
+            status,
+            track,
+            position: player.elapsed(),
+            volume: player.volume(),
+        }
     }
 
     pub fn update(&mut self) {
-        if self.finished_current_track() {
+        if self.core.follow_player() {
+            // the next track took over without a gap
+        } else if self.core.player.is_finished() {
             if let Some(audio) = self.core.next.take() {
                 self.try_play(audio);
                 self.core.queue.advance()
@@ -392,14 +1114,18 @@ impl State {
             }
         } else if self.core.player.is_empty() {
             if let Some(audio) = self.core.next.take() {
-                self.try_play(audio)
+                match self.core.resume.take() {
+                    Some(resume) => self.resume(audio, resume),
+                    None => self.try_play(audio),
+                }
             }
         }
 
+        self.try_do(|this| this.core.queue_next().map_err(<_>::into));
         self.core.maybe_fetch_next();
 
         if !self.core.player.is_paused() {
-            self.widgets.release.play(self.core.queue.index())
+            self.widgets.release.play(self.core.playing_in_release())
         }
     }
 
@@ -407,12 +1133,23 @@ impl State {
         StateConfig {
             general: cfg::General {
                 volume: self.core.player.volume(),
+                download_dir: self.download_dir,
+                crossfade: self.core.crossfade.as_secs_f32(),
             },
             bindings: self.core.bindings.into(),
         }
     }
 }
 
+/// The directory to download to if none is configured.
+fn default_download_dir() -> Option<PathBuf> {
+    let dirs = UserDirs::new()?;
+
+    dirs.audio_dir()
+        .or_else(|| dirs.download_dir())
+        .map(|dir| dir.join("justbc"))
+}
+
 fn saturating_sub(a: Duration, b: Duration) -> Duration {
     a.checked_sub(b).unwrap_or_default()
 }
@@ -424,3 +1161,111 @@ fn can_select_down(selected: Option<usize>, len: usize) -> bool {
 fn can_select_up(selected: Option<usize>) -> bool {
     selected.unwrap_or_default() > 0
 }
+
+/// Parses text like `#genre` or `#genre/subgenre` as the releases
+/// to discover. A lone `#` discovers releases of all genres.
+fn discover_tag(text: &str) -> Option<DiscoverArgs> {
+    fn tag(s: &str) -> Option<String> {
+        let tag = s.split_whitespace().collect::<Vec<_>>().join("-");
+        Some(tag.to_lowercase()).filter(|tag| !tag.is_empty())
+    }
+
+    let text = text.trim().strip_prefix('#')?;
+
+    let (genre, subgenre) = match text.split_once('/') {
+        Some((genre, subgenre)) => (tag(genre), tag(subgenre)),
+        None => (tag(text), None),
+    };
+
+    Some(DiscoverArgs::new(genre, subgenre))
+}
+
+/// Parses the provided text as a link to a page on
+/// bandcamp.com, if it is one.
+fn bandcamp_url(text: &str) -> Option<Url> {
+    text.trim()
+        .parse()
+        .ok()
+        .filter(|url| Page::from_url(url).is_some())
+}
+
+#[cfg(test)]
+mod test {
+    use {super::*, crate::play::test::release, bandcamp_api::Api, std::fs};
+
+    /// A state for the test called `name`, with its own library file.
+    pub(super) fn state(name: &str, queue: Queue) -> State {
+        let (fetcher, _) = Fetcher::new(Api::new().offline());
+        let library = std::env::temp_dir().join(format!(
+            "justbc-library-{}-{}.toml",
+            name,
+            std::process::id()
+        ));
+
+        State::new(
+            <_>::default(),
+            LibraryState::load(library).unwrap(),
+            queue,
+            <_>::default(),
+            fetcher,
+        )
+    }
+
+    #[test]
+    fn wants_lyrics() {
+        let mut with_lyrics = release(1, 2);
+        with_lyrics.tracks[1].has_lyrics = true;
+
+        let mut state = state("wants_lyrics", Queue::default());
+
+        // just browsing
+        assert!(!state.wants_lyrics(&with_lyrics));
+        assert!(!state.wants_lyrics(&release(1, 2)));
+
+        state.core.incoming = Some(Incoming {
+            id: with_lyrics.info.id,
+            play: Some(0),
+        });
+        assert!(state.wants_lyrics(&with_lyrics));
+
+        state.core.incoming = None;
+        state.core.queue.append(&with_lyrics, 0);
+        assert!(state.wants_lyrics(&with_lyrics));
+
+        let mut other = release(2, 1);
+        other.tracks[0].has_lyrics = true;
+        assert!(!state.wants_lyrics(&other));
+
+        state.toggle_lyrics();
+        assert!(state.wants_lyrics(&other));
+    }
+
+    #[test]
+    fn resume() {
+        let file = std::env::temp_dir().join(format!("justbc-session-{}.toml", std::process::id()));
+
+        Session {
+            elapsed: 12.5,
+            ..<_>::default()
+        }
+        .save(&file)
+        .unwrap();
+
+        let mut queue = Queue::default();
+        queue.set_release(&release(1, 2), 1);
+
+        let mut state = state("resume", queue);
+        state.restore(Session::load(&file).unwrap());
+        fs::remove_file(file).unwrap();
+
+        let resume = state.core.resume.unwrap();
+        assert_eq!(resume.elapsed, Duration::from_secs_f64(12.5));
+        assert!(resume.paused);
+
+        // the position is kept until the track is loaded
+        assert_eq!(state.session().elapsed, 12.5);
+
+        state.toggle_play();
+        assert!(!state.core.resume.unwrap().paused);
+    }
+}
@@ -11,3 +11,30 @@ pub fn terminal() -> io::Result<Terminal> {
 
     Ok(term)
 }
+
+/// Returns the size of a cell in pixels, if the terminal reports it.
+#[cfg(unix)]
+pub fn cell_size() -> Option<(u16, u16)> {
+    let mut size = libc::winsize {
+        ws_row: 0,
+        ws_col: 0,
+        ws_xpixel: 0,
+        ws_ypixel: 0,
+    };
+
+    // SAFETY: `TIOCGWINSZ` only writes to the `winsize` it's passed
+    let result = unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) };
+
+    if result != 0 || size.ws_col == 0 || size.ws_row == 0 {
+        return None
+    }
+
+    let cell = (size.ws_xpixel / size.ws_col, size.ws_ypixel / size.ws_row);
+
+    (cell.0 > 0 && cell.1 > 0).then_some(cell)
+}
+
+#[cfg(not(unix))]
+pub fn cell_size() -> Option<(u16, u16)> {
+    None
+}
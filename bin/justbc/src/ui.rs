@@ -1,15 +1,25 @@
 use {
     crate::{
         cfg,
-        state::{Active, Core, ExploreState, State, WidgetState},
+        play::QueuedTrack,
+        state::{Active, ArtCache, ExploreState, State, WidgetState},
     },
-    bandcamp_api::data::releases::{Release, Stream, Track},
-    bc_tui::{nav::NavView, releases::ReleaseView, tracks::PlayBar},
+    bandcamp_api::data::{
+        common::{Art, Id},
+        discover::Discover,
+        releases::{Release, Track},
+    },
+    bc_tui::{nav::NavView, queue::QueueItem, releases::ReleaseView, tracks::PlayBar},
+    fetch::Progress,
     gen_tui::{
         layout::{Margin, RectExt},
-        widgets::{StatefulWidgetExt, WidgetExt},
+        widgets::{
+            image::{Graphics, Pixels},
+            StatefulWidgetExt,
+            WidgetExt,
+        },
     },
-    std::error::Error,
+    std::{collections::BTreeMap, error::Error},
     tui::{
         backend::Backend,
         layout::{Alignment, Rect},
@@ -39,22 +49,86 @@ impl From<Rect> for Layout {
     }
 }
 
-pub fn draw(frame: &mut Frame<impl Backend>, state: &mut State, cfg: &cfg::Graphics) {
+/// Draws the whole interface, placing cover art in `graphics`
+/// if there is a way to draw it.
+pub fn draw(
+    frame: &mut Frame<impl Backend>,
+    state: &mut State,
+    cfg: &cfg::Graphics,
+    graphics: Option<&mut Graphics>,
+) {
     let area = frame.size();
-    let layout = Layout::from(area);
+    let mut layout = Layout::from(area);
     let accent = Style::default().fg(cfg.accent);
 
+    let release_art = state
+        .core
+        .release
+        .as_ref()
+        .and_then(|release| release.info.art_id)
+        .filter(|_| graphics.is_some());
+    let playing_art = state
+        .core
+        .queue
+        .current()
+        .and_then(|track| track.art)
+        .filter(|_| graphics.is_some());
+
+    state.request_art(release_art);
+    state.request_art(playing_art);
+
+    let covers = &state.art;
+    let current = state.core.queue.current();
+    let lyrics = &mut state.widgets.lyrics;
+    lyrics.follow(current.map(|track| (track.release, track.number)));
+
+    if lyrics.shown {
+        let (release, rest) = layout.left.split_ratio_y(0.5);
+        layout.left = release;
+
+        draw_lyrics(current, lyrics.scroll, rest, frame)
+    }
+
     if let Some(release) = state.core.release.as_ref() {
-        draw_playing(
-            release,
-            &state.core,
-            &mut state.widgets,
-            layout,
-            accent,
-            frame,
-        )
+        let art = small_art(covers, release_art);
+        draw_release(release, art, &mut state.widgets, layout, accent, frame)
     } else {
-        draw_placeholders(state, layout, frame)
+        draw_release_placeholder(layout, frame)
+    }
+
+    let downloads = download_status(&state.downloads);
+
+    if let Some(track) = state.core.queue.current() {
+        PlayBar::new(&track.artist, &track.track)
+            .art(small_art(covers, playing_art))
+            .elapsed(state.core.player.elapsed())
+            .volume(state.core.player.volume())
+            .shuffle(state.core.queue.is_shuffled())
+            .repeat(state.core.queue.repeat())
+            .bar_style(accent)
+            .with_container()
+            .title(downloads)
+            .borders(Borders::TOP)
+            .render_to(frame, layout.bottom, &mut state.widgets.play_bar)
+    } else {
+        PlayBar::new("Nothing playing", &dummy_track())
+            .volume(state.core.player.volume())
+            .with_container()
+            .title(downloads)
+            .borders(Borders::TOP)
+            .render_to(frame, layout.bottom, &mut state.widgets.play_bar)
+    }
+
+    let mut graphics = graphics;
+
+    if let Some(graphics) = graphics.as_mut() {
+        for id in state.art.take_evicted() {
+            graphics.forget(id.get())
+        }
+
+        if state.error.is_none() {
+            place_art(graphics, state, release_art, playing_art, layout)
+        }
     }
 
     draw_nav(state, layout, accent, frame);
@@ -62,17 +136,63 @@ pub fn draw(frame: &mut Frame<impl Backend>, state: &mut State, cfg: &cfg::Graph
     if let Some(error) = state.error.as_deref() {
         draw_error(error, frame, area)
     }
+
+    if let Some(graphics) = graphics {
+        frame.render_widget(graphics, area)
+    }
+}
+
+fn small_art(covers: &ArtCache, id: Option<Id<Art>>) -> Option<&Pixels> {
+    Some(&covers.get(id?)?.small)
 }
 
-fn draw_playing(
+/// Places the cover art where its preview was drawn, so it's shown
+/// in full resolution if the terminal supports it.
+fn place_art(
+    graphics: &mut Graphics,
+    state: &State,
+    release_art: Option<Id<Art>>,
+    playing_art: Option<Id<Art>>,
+    layout: Layout,
+) {
+    let widgets = &state.widgets;
+    let scroll = widgets.release_scroll;
+
+    // the release is drawn scrolled, so only art that's still
+    // entirely visible is placed
+    let release_area = widgets
+        .release
+        .art_area()
+        .filter(|art| art.y >= layout.left.y + scroll)
+        .map(|art| Rect {
+            y: art.y - scroll,
+            ..art
+        });
+
+    let placements = [
+        (release_art, release_area),
+        (playing_art, widgets.play_bar.art_area()),
+    ];
+
+    for (id, area) in placements.iter() {
+        if let (Some(id), Some(area)) = (id, area) {
+            if let Some(cover) = state.art.get(*id) {
+                graphics.place(id.get(), &cover.full, *area)
+            }
+        }
+    }
+}
+
+fn draw_release(
     release: &Release,
-    core: &Core,
+    art: Option<&Pixels>,
     widgets: &mut WidgetState,
     layout: Layout,
     accent: Style,
     frame: &mut Frame<impl Backend>,
 ) {
     ReleaseView::new(release)
+        .art(art)
         .playing_style(accent)
         .scrollable()
         .scroll_y(widgets.release_scroll)
@@ -80,19 +200,9 @@ fn draw_playing(
         .margin(Margin::right(1))
         .borders(Borders::RIGHT)
         .render_to(frame, layout.left, &mut widgets.release);
-
-    if let Some(track) = core.queue.current() {
-        PlayBar::new(&release.info.artist, track)
-            .elapsed(core.player.elapsed())
-            .volume(core.player.volume())
-            .bar_style(accent)
-            .with_container()
-            .borders(Borders::TOP)
-            .render_to(frame, layout.bottom, &mut widgets.play_bar)
-    }
 }
 
-fn draw_placeholders(state: &mut State, layout: Layout, frame: &mut Frame<impl Backend>) {
+fn draw_release_placeholder(layout: Layout, frame: &mut Frame<impl Backend>) {
     Paragraph::new("No release")
         .alignment(Alignment::Center)
         .with_container()
@@ -103,34 +213,112 @@ fn draw_placeholders(state: &mut State, layout: Layout, frame: &mut Frame<impl B
         })
         .borders(Borders::RIGHT)
         .render_to(frame, layout.left);
+}
+
+/// Draws the lyrics of the playing track, if it has any.
+fn draw_lyrics(
+    track: Option<&QueuedTrack>,
+    scroll: u16,
+    area: Rect,
+    frame: &mut Frame<impl Backend>,
+) {
+    let lyrics = match track.map(|queued| &queued.track) {
+        Some(track) => match &track.lyrics {
+            Some(lyrics) => lyrics.as_str(),
+            None if track.has_lyrics => "Fetching lyrics...",
+            None => "No lyrics",
+        },
+        None => "Nothing playing",
+    };
 
-    PlayBar::new("Nothing playing", &dummy_track())
-        .volume(state.core.player.volume())
+    Paragraph::new(lyrics)
+        .wrap(Wrap { trim: false })
+        .scrollable()
+        .scroll_y(scroll)
         .with_container()
-        .borders(Borders::TOP)
-        .render_to(frame, layout.bottom, &mut state.widgets.play_bar)
+        .title(" Lyrics ")
+        .margin(Margin::right(1))
+        .borders(Borders::TOP | Borders::RIGHT)
+        .render_to(frame, area);
 }
 
 fn draw_nav(state: &mut State, layout: Layout, accent: Style, frame: &mut Frame<impl Backend>) {
+    let queue = &state.core.queue;
+    let items = queue
+        .tracks()
+        .iter()
+        .map(|track| QueueItem {
+            artist: &track.artist,
+            track: &track.track,
+            queued: track.queued,
+        })
+        .collect::<Vec<_>>();
+
     let mut nav = NavView::default();
     nav = match state.navigation.active {
-        Active::Library => nav.library(),
+        Active::Library => nav.library(
+            &state.navigation.library.library,
+            &state.navigation.library.filter,
+        ),
         Active::Explore => match &state.navigation.explore {
             ExploreState::Blank(_) => nav.blank(),
             ExploreState::Loading(_) => nav.loading(),
             ExploreState::Outlet(o) => nav.outlet(o),
             ExploreState::Release(r) => nav.release(r),
             ExploreState::Search(s) => nav.search(s),
+            ExploreState::Discover(d) => nav.discover(d),
             ExploreState::Fan(f) => nav.fan(f),
         },
+        Active::Queue => nav.queue(&items, queue.current().map(|_| queue.index())),
     };
 
+    if state.navigation.active == Active::Explore {
+        let hint = match &state.navigation.explore {
+            ExploreState::Discover(Discover {
+                args: Some(args), ..
+            }) => args.sort.name(),
+            _ => state.navigation.search_filter.name(),
+        };
+
+        nav = nav.search_hint(Some(hint))
+    }
+
     nav.highlight_style(accent)
         .with_container()
         .margin(Margin::left(1))
         .render_to(frame, layout.right, &mut state.widgets.nav);
 }
 
+/// Describes the progress of the first batch of downloads
+/// and how many others are running.
+fn download_status(downloads: &BTreeMap<usize, Option<Progress>>) -> String {
+    let mut batches = downloads.values();
+
+    let status = match batches.next() {
+        Some(Some(progress)) => {
+            let percent = progress
+                .fraction
+                .map(|fraction| format!(" {:.0}%", fraction * 100.))
+                .unwrap_or_default();
+
+            format!(
+                " Downloading {} ({}/{}){}",
+                progress.title,
+                progress.track + 1,
+                progress.tracks,
+                percent
+            )
+        }
+        Some(None) => " Starting download".into(),
+        None => return String::new(),
+    };
+
+    match batches.len() {
+        0 => format!("{} ", status),
+        more => format!("{}, {} more queued ", status, more),
+    }
+}
+
 fn draw_error(error: &dyn Error, frame: &mut Frame<impl Backend>, area: Rect) {
     const SCALE: u16 = 3;
 
@@ -149,10 +337,14 @@ fn draw_error(error: &dyn Error, frame: &mut Frame<impl Backend>, area: Rect) {
 
 fn dummy_track() -> Track {
     Track {
+        id: None,
+        track_num: None,
         title: String::new(),
-        stream: Stream {
-            mp3_128: "r://".parse().unwrap(),
-        },
+        artist: None,
         duration: <_>::default(),
+        stream: None,
+        is_streamable: false,
+        has_lyrics: false,
+        lyrics: None,
     }
 }
@@ -0,0 +1,63 @@
+use {
+    crate::{
+        data::Query,
+        error::{Status, Transport},
+        parse,
+        Result,
+    },
+    reqwest::Client,
+    snafu::ResultExt,
+};
+
+/// Like [Api](crate::Api), but doesn't block while requests are in flight,
+/// so it can be used from within an async runtime. Responses aren't cached.
+#[derive(Clone, Default)]
+pub struct AsyncApi {
+    client: Client,
+}
+
+impl AsyncApi {
+    pub fn new() -> AsyncApi {
+        <_>::default()
+    }
+
+    pub fn with_client(client: Client) -> AsyncApi {
+        AsyncApi { client }
+    }
+
+    pub async fn query<T, A>(&self, args: &A) -> Result<T>
+    where
+        T: Query<A>,
+        A: ?Sized,
+    {
+        let url = T::url(args);
+
+        let response = self
+            .client
+            .get(url.clone())
+            .send()
+            .await
+            .context(Transport { endpoint: &url })?;
+
+        let status = response.status();
+
+        if !status.is_success() {
+            return Status {
+                status,
+                endpoint: url,
+            }
+            .fail()
+        }
+
+        let body = response
+            .bytes()
+            .await
+            .context(Transport { endpoint: &url })?;
+
+        parse::<T, A>(&body, &url)
+    }
+
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+}
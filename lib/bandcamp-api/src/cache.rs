@@ -0,0 +1,102 @@
+use {
+    std::{
+        fs,
+        io,
+        path::PathBuf,
+        time::{Duration, SystemTime},
+    },
+    url::Url,
+};
+
+/// Stores raw API responses on disk, keyed by their URL.
+#[derive(Debug, Clone)]
+pub struct Cache {
+    dir: PathBuf,
+    ttl: Duration,
+}
+
+/// A response read from the cache.
+pub struct Entry {
+    pub body: Vec<u8>,
+    /// Whether the response is older than the cache's time to live.
+    pub expired: bool,
+}
+
+impl Cache {
+    /// Creates a cache in the provided directory, whose
+    /// entries expire after the provided duration.
+    pub fn new(dir: impl Into<PathBuf>, ttl: Duration) -> Cache {
+        Cache {
+            dir: dir.into(),
+            ttl,
+        }
+    }
+
+    pub fn get(&self, url: &Url) -> Option<Entry> {
+        let path = self.path(url);
+        let age = fs::metadata(&path)
+            .and_then(|meta| meta.modified())
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+            .unwrap_or_default();
+
+        fs::read(path).ok().map(|body| Entry {
+            body,
+            expired: age > self.ttl,
+        })
+    }
+
+    pub fn put(&self, url: &Url, body: &[u8]) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+
+        // writing to a temporary file first means readers
+        // never see a partially written response
+        let path = self.path(url);
+        let partial = path.with_extension("part");
+
+        fs::write(&partial, body)?;
+        fs::rename(partial, path)
+    }
+
+    fn path(&self, url: &Url) -> PathBuf {
+        self.dir.join(format!("{:016x}.json", fnv1a(url.as_str())))
+    }
+}
+
+/// A hash that, unlike the standard library's,
+/// stays the same across releases and platforms.
+fn fnv1a(s: &str) -> u64 {
+    const OFFSET: u64 = 0xCBF2_9CE4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01B3;
+
+    s.bytes().fold(OFFSET, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(PRIME)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn roundtrip() {
+        let dir = std::env::temp_dir().join(format!("bandcamp-api-cache-{}", std::process::id()));
+        let url = "https://bandcamp.com/api/mobile/24/band_details?band_id=1"
+            .parse()
+            .unwrap();
+
+        let cache = Cache::new(&dir, Duration::from_secs(60));
+        assert!(cache.get(&url).is_none());
+
+        cache.put(&url, b"{}").unwrap();
+        let entry = cache.get(&url).unwrap();
+        assert_eq!(entry.body, b"{}");
+        assert!(!entry.expired);
+
+        let expired = Cache::new(&dir, Duration::ZERO);
+        std::thread::sleep(Duration::from_millis(10));
+        assert!(expired.get(&url).unwrap().expired);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
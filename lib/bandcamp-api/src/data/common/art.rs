@@ -0,0 +1,38 @@
+use {super::Id, url::Url};
+
+/// The cover art of a release.
+pub enum Art {}
+
+/// The sizes cover art is available in on Bandcamp's image server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArtSize {
+    /// 100 by 100 pixels.
+    Thumbnail,
+    /// 350 by 350 pixels.
+    Medium,
+    /// 1200 by 1200 pixels.
+    Large,
+}
+
+impl ArtSize {
+    fn format(self) -> u8 {
+        match self {
+            ArtSize::Thumbnail => 3,
+            ArtSize::Medium => 2,
+            ArtSize::Large => 10,
+        }
+    }
+}
+
+impl Id<Art> {
+    /// Returns the URL of the image in the provided size.
+    pub fn url(&self, size: ArtSize) -> Url {
+        format!(
+            "https://f4.bcbits.com/img/a{:010}_{}.jpg",
+            self,
+            size.format()
+        )
+        .parse()
+        .unwrap()
+    }
+}
@@ -1,7 +1,7 @@
 use {
     super::{super::parse, Date, Month},
     practicaltimestamp::UnixTimestamp,
-    serde::Deserialize,
+    serde::{Deserialize, Serialize},
     snafu::{OptionExt, ResultExt, Snafu},
     std::{num::NonZeroU8, str::FromStr},
 };
@@ -64,6 +64,15 @@ impl<'de> Deserialize<'de> for Date {
     }
 }
 
+impl Serialize for Date {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(&self.fmt_short())
+    }
+}
+
 impl FromStr for Month {
     type Err = DateParseError;
 
@@ -1,20 +1,41 @@
 #[cfg(feature = "query")]
 pub(crate) mod parse;
 
+pub mod art;
 pub mod date;
 
-pub use date::{Date, Month};
+pub use {
+    art::{Art, ArtSize},
+    date::{Date, Month},
+};
 
-use std::{fmt, marker::PhantomData};
+use std::{
+    fmt,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+};
 
 #[cfg(feature = "query")]
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 pub struct Id<T> {
     value: u64,
     _marker: PhantomData<T>,
 }
 
+impl<T> Id<T> {
+    pub const fn new(value: u64) -> Id<T> {
+        Id {
+            value,
+            _marker: PhantomData,
+        }
+    }
+
+    pub const fn get(&self) -> u64 {
+        self.value
+    }
+}
+
 impl<T> Clone for Id<T> {
     fn clone(&self) -> Self {
         *self
@@ -31,6 +52,12 @@ impl<T> PartialEq for Id<T> {
 
 impl<T> Eq for Id<T> {}
 
+impl<T> Hash for Id<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.value.hash(state)
+    }
+}
+
 #[cfg(feature = "query")]
 impl<'de, T> Deserialize<'de> for Id<T> {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
@@ -44,6 +71,16 @@ impl<'de, T> Deserialize<'de> for Id<T> {
     }
 }
 
+#[cfg(feature = "query")]
+impl<T> Serialize for Id<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.value.serialize(serializer)
+    }
+}
+
 impl<T> fmt::Display for Id<T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         self.value.fmt(f)
@@ -12,6 +12,13 @@ where
     Parse { source: E, field: &'static str },
 }
 
+pub fn serialize_url<S>(url: &url::Url, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.serialize_str(url.as_str())
+}
+
 pub fn deserialize_url<'de, D>(deserializer: D) -> Result<url::Url, D::Error>
 where
     D: serde::Deserializer<'de>,
@@ -22,3 +29,8 @@ where
         .parse()
         .map_err(serde::de::Error::custom)
 }
+
+/// The default of flags that are set unless stated otherwise.
+pub fn yes() -> bool {
+    true
+}
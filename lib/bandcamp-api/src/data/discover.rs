@@ -0,0 +1,235 @@
+#[cfg(feature = "query")]
+use {
+    crate::{data::Query, url::ApiUrl},
+    serde::{Deserialize, Serialize},
+    url::Url,
+};
+
+use crate::data::{
+    common::{Art, Id},
+    outlets,
+    releases::{self, ReleaseKind},
+};
+
+/// Releases picked by Bandcamp for a genre and tag.
+#[derive(Debug)]
+#[cfg_attr(feature = "query", derive(Deserialize, Serialize))]
+pub struct Discover {
+    pub items: Vec<Discovered>,
+    /// How many releases there are in total across all pages.
+    #[cfg_attr(feature = "query", serde(rename(deserialize = "total_count")))]
+    pub total: u64,
+    /// The arguments the releases were queried with, if they're known.
+    #[cfg_attr(feature = "query", serde(skip))]
+    pub args: Option<DiscoverArgs>,
+}
+
+impl Discover {
+    /// Returns the arguments for the next page of releases, if there is one.
+    pub fn next_page(&self) -> Option<DiscoverArgs> {
+        self.args
+            .as_ref()
+            .filter(|_| (self.items.len() as u64) < self.total)
+            .map(DiscoverArgs::next_page)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiscoverArgs {
+    /// One of Bandcamp's genres, e.g. `electronic` or `hip-hop-rap`.
+    /// If there is none, releases of all genres are discovered.
+    pub genre: Option<String>,
+    /// A tag within the genre, e.g. `house`.
+    pub subgenre: Option<String>,
+    pub sort: DiscoverSort,
+    pub format: DiscoverFormat,
+    /// The GeoNames id of the place the releases are from,
+    /// or 0 for anywhere.
+    pub location: u64,
+    /// Starts at 0.
+    pub page: u16,
+}
+
+impl DiscoverArgs {
+    /// Discovers the releases of the provided genre and subgenre,
+    /// using the default filters.
+    pub fn new(genre: Option<String>, subgenre: Option<String>) -> DiscoverArgs {
+        DiscoverArgs {
+            genre,
+            subgenre,
+            sort: <_>::default(),
+            format: <_>::default(),
+            location: 0,
+            page: 0,
+        }
+    }
+
+    pub fn next_page(&self) -> DiscoverArgs {
+        DiscoverArgs {
+            page: self.page + 1,
+            ..self.clone()
+        }
+    }
+
+    /// Whether these arguments are for a different page of the
+    /// same releases as the provided ones.
+    pub fn continues(&self, other: &DiscoverArgs) -> bool {
+        DiscoverArgs {
+            page: other.page,
+            ..self.clone()
+        } == *other
+    }
+}
+
+#[cfg(feature = "query")]
+impl Query<DiscoverArgs> for Discover {
+    const NAME: &'static str = "discovered releases";
+    const CACHED: bool = true;
+
+    fn url(args: &DiscoverArgs) -> Url {
+        ApiUrl::new("discover")
+            .version("3")
+            .function("get_web")
+            .query("g", args.genre.as_deref().unwrap_or("all"))
+            .query("t", args.subgenre.as_deref().unwrap_or_default())
+            .query("s", args.sort.identifier())
+            .query("f", args.format.identifier())
+            .query("gn", args.location.to_string())
+            .query("p", args.page.to_string())
+            .into()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(
+    feature = "query",
+    derive(Deserialize, Serialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum DiscoverSort {
+    #[default]
+    BestSelling,
+    New,
+    /// Recommended by artists.
+    Recommended,
+}
+
+impl DiscoverSort {
+    pub fn cycle(self) -> DiscoverSort {
+        use DiscoverSort::*;
+
+        match self {
+            BestSelling => New,
+            New => Recommended,
+            Recommended => BestSelling,
+        }
+    }
+
+    /// The identifier Bandcamp uses for the order.
+    pub fn identifier(self) -> &'static str {
+        match self {
+            DiscoverSort::BestSelling => "top",
+            DiscoverSort::New => "new",
+            DiscoverSort::Recommended => "rec",
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            DiscoverSort::BestSelling => "best-selling",
+            DiscoverSort::New => "new arrivals",
+            DiscoverSort::Recommended => "artist-recommended",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(
+    feature = "query",
+    derive(Deserialize, Serialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum DiscoverFormat {
+    #[default]
+    All,
+    Digital,
+    Vinyl,
+    Cd,
+    Cassette,
+}
+
+impl DiscoverFormat {
+    /// The identifier Bandcamp uses for the format.
+    pub fn identifier(self) -> &'static str {
+        match self {
+            DiscoverFormat::All => "all",
+            DiscoverFormat::Digital => "digital",
+            DiscoverFormat::Vinyl => "vinyl",
+            DiscoverFormat::Cd => "cd",
+            DiscoverFormat::Cassette => "cassette",
+        }
+    }
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "query", derive(Deserialize, Serialize))]
+pub struct Discovered {
+    #[cfg_attr(feature = "query", serde(rename(deserialize = "type")))]
+    pub kind: ReleaseKind,
+    pub id: Id<releases::Release>,
+    #[cfg_attr(feature = "query", serde(rename(deserialize = "primary_text")))]
+    pub title: String,
+    #[cfg_attr(feature = "query", serde(rename(deserialize = "secondary_text")))]
+    pub artist: String,
+    #[cfg_attr(feature = "query", serde(rename(deserialize = "band_id")))]
+    pub artist_id: Id<outlets::Outlet>,
+    #[cfg_attr(feature = "query", serde(default, rename(deserialize = "genre_text")))]
+    pub genre: Option<String>,
+    #[cfg_attr(feature = "query", serde(default))]
+    pub art_id: Option<Id<Art>>,
+}
+
+#[cfg(all(test, feature = "query"))]
+mod test {
+    use {super::*, crate::data::common::ArtSize};
+
+    #[test]
+    fn pages() {
+        let json = r#"{
+            "items": [{
+                "type": "a",
+                "id": 1,
+                "primary_text": "Album",
+                "secondary_text": "Artist",
+                "band_id": 2,
+                "genre_text": "electronic",
+                "art_id": 3
+            }],
+            "total_count": 2
+        }"#;
+
+        let args = DiscoverArgs::new(Some("electronic".into()), None);
+        let mut discover: Discover = serde_json::from_str(json).unwrap();
+        assert_eq!(discover.items[0].kind, ReleaseKind::Album);
+        assert_eq!(
+            discover.items[0]
+                .art_id
+                .unwrap()
+                .url(ArtSize::Thumbnail)
+                .as_str(),
+            "https://f4.bcbits.com/img/a0000000003_3.jpg"
+        );
+        assert_eq!(discover.next_page(), None);
+
+        discover.args = Some(args.clone());
+        let next = discover.next_page().unwrap();
+        assert_eq!(next.page, 1);
+        assert!(next.continues(&args));
+        assert!(!DiscoverArgs::new(None, None).continues(&args));
+
+        assert_eq!(
+            Discover::url(&next).as_str(),
+            "https://bandcamp.com/api/discover/3/get_web?g=electronic&t=&s=top&f=all&gn=0&p=1"
+        );
+    }
+}
@@ -8,11 +8,11 @@ use {
         },
         url::ApiUrl,
     },
-    serde::Deserialize,
+    serde::{Deserialize, Serialize},
 };
 
 #[derive(Debug)]
-#[cfg_attr(feature = "query", derive(Deserialize))]
+#[cfg_attr(feature = "query", derive(Deserialize, Serialize))]
 pub struct Fan {
     pub name: String,
     pub id: Id<Fan>,
@@ -33,6 +33,9 @@ impl FanArgs {
 }
 
 impl Query<FanArgs> for Fan {
+    const NAME: &'static str = "fan";
+    const CACHED: bool = true;
+
     fn url(args: &FanArgs) -> url::Url {
         ApiUrl::mobile()
             .function("fan_details")
@@ -44,16 +47,16 @@ impl Query<FanArgs> for Fan {
 }
 
 #[derive(Debug)]
-#[cfg_attr(feature = "query", derive(Deserialize))]
+#[cfg_attr(feature = "query", derive(Deserialize, Serialize))]
 pub struct Collected {
-    #[cfg_attr(feature = "query", serde(rename = "tralbum_type"))]
+    #[cfg_attr(feature = "query", serde(rename(deserialize = "tralbum_type")))]
     pub kind: ReleaseKind,
-    #[cfg_attr(feature = "query", serde(rename = "item_title"))]
+    #[cfg_attr(feature = "query", serde(rename(deserialize = "item_title")))]
     pub title: String,
-    #[cfg_attr(feature = "query", serde(rename = "item_id"))]
+    #[cfg_attr(feature = "query", serde(rename(deserialize = "item_id")))]
     pub id: Id<releases::Release>,
-    #[cfg_attr(feature = "query", serde(rename = "band_name"))]
+    #[cfg_attr(feature = "query", serde(rename(deserialize = "band_name")))]
     pub artist: String,
-    #[cfg_attr(feature = "query", serde(rename = "band_id"))]
+    #[cfg_attr(feature = "query", serde(rename(deserialize = "band_id")))]
     pub artist_id: Id<outlets::Outlet>,
 }
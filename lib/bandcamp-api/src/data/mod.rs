@@ -1,4 +1,5 @@
 pub mod common;
+pub mod discover;
 pub mod fans;
 pub mod outlets;
 pub mod releases;
@@ -6,5 +7,12 @@ pub mod search;
 
 #[cfg(feature = "query")]
 pub trait Query<A: ?Sized>: serde::de::DeserializeOwned {
+    /// What the query fetches, for use in error messages.
+    const NAME: &'static str;
+
+    /// Whether responses to this query are stored in the
+    /// [Cache](crate::cache::Cache).
+    const CACHED: bool = false;
+
     fn url(args: &A) -> url::Url;
 }
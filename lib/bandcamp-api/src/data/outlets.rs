@@ -1,7 +1,7 @@
 #[cfg(feature = "query")]
 use {
     crate::{data::Query, url::ApiUrl},
-    serde::Deserialize,
+    serde::{Deserialize, Serialize},
     url::Url,
 };
 
@@ -11,7 +11,7 @@ use crate::data::{
 };
 
 #[derive(Debug, Clone)]
-#[cfg_attr(feature = "query", derive(Deserialize))]
+#[cfg_attr(feature = "query", derive(Deserialize, Serialize))]
 pub struct Outlet {
     #[cfg_attr(feature = "query", serde(flatten))]
     pub info: Info,
@@ -20,6 +20,9 @@ pub struct Outlet {
 
 #[cfg(feature = "query")]
 impl Query<Id<Outlet>> for Outlet {
+    const NAME: &'static str = "artist or label";
+    const CACHED: bool = true;
+
     fn url(id: &Id<Outlet>) -> Url {
         ApiUrl::mobile()
             .function("band_details")
@@ -29,11 +32,14 @@ impl Query<Id<Outlet>> for Outlet {
 }
 
 #[derive(Debug, Clone)]
-#[cfg_attr(feature = "query", derive(Deserialize))]
+#[cfg_attr(feature = "query", derive(Deserialize, Serialize))]
 pub struct Info {
     #[cfg_attr(
         feature = "query",
-        serde(rename = "artists", deserialize_with = "guess_outlet_kind")
+        serde(
+            rename(deserialize = "artists"),
+            deserialize_with = "guess_outlet_kind"
+        )
     )]
     pub kind: OutletKind,
     pub name: String,
@@ -43,6 +49,11 @@ pub struct Info {
 }
 
 #[derive(Debug, Copy, PartialEq, Eq, Clone)]
+#[cfg_attr(
+    feature = "query",
+    derive(Deserialize, Serialize),
+    serde(rename_all = "kebab-case")
+)]
 pub enum OutletKind {
     Artist,
     Label,
@@ -66,13 +77,13 @@ where
 }
 
 #[derive(Debug, Clone)]
-#[cfg_attr(feature = "query", derive(Deserialize))]
+#[cfg_attr(feature = "query", derive(Deserialize, Serialize))]
 pub struct Release {
-    #[cfg_attr(feature = "query", serde(rename = "item_type"))]
+    #[cfg_attr(feature = "query", serde(rename(deserialize = "item_type")))]
     pub kind: ReleaseKind,
-    #[cfg_attr(feature = "query", serde(rename = "item_id"))]
+    #[cfg_attr(feature = "query", serde(rename(deserialize = "item_id")))]
     pub id: Id<releases::Release>,
     pub title: String,
-    #[cfg_attr(feature = "query", serde(rename = "artist_name"))]
+    #[cfg_attr(feature = "query", serde(rename(deserialize = "artist_name")))]
     pub artist: Option<String>,
 }
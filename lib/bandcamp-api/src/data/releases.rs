@@ -1,30 +1,64 @@
 #[cfg(feature = "query")]
 use {
     crate::{
-        data::{
-            common::{self, Id},
-            outlets::Outlet,
-            Query,
-        },
+        data::{common, Query},
         url::ApiUrl,
     },
-    serde::Deserialize,
+    serde::{Deserialize, Serialize},
 };
 
 use {
-    crate::data::common::Date,
-    std::{fmt, time::Duration},
+    crate::data::{
+        common::{Art, Date, Id},
+        outlets::Outlet,
+    },
+    std::{collections::HashMap, fmt, time::Duration},
 };
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-#[cfg_attr(feature = "query", derive(Deserialize))]
+#[cfg_attr(feature = "query", derive(Deserialize, Serialize))]
 pub struct Release {
     #[cfg_attr(feature = "query", serde(flatten))]
     pub info: Info,
     pub tracks: Vec<Track>,
 }
 
-#[derive(Clone, Copy)]
+#[cfg(feature = "query")]
+impl Release {
+    /// Returns the arguments needed to query this release again.
+    pub fn args(&self) -> ReleaseArgs {
+        ReleaseArgs {
+            id: self.info.id,
+            kind: self.info.kind,
+            outlet: self.info.band.id,
+        }
+    }
+
+    /// Fills in the lyrics of the tracks that have them.
+    pub fn set_lyrics(&mut self, lyrics: &Lyrics) {
+        for track in &mut self.tracks {
+            track.set_lyrics(lyrics)
+        }
+    }
+
+    /// Whether any of the tracks have lyrics that haven't been fetched yet.
+    pub fn lacks_lyrics(&self) -> bool {
+        self.tracks
+            .iter()
+            .any(|track| track.has_lyrics && track.lyrics.is_none())
+    }
+
+    /// Whether the tracks are by different artists,
+    /// which is the case for compilations and splits.
+    pub fn is_compilation(&self) -> bool {
+        self.tracks
+            .iter()
+            .filter_map(|track| track.artist.as_deref())
+            .any(|artist| artist != self.info.artist)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
 #[cfg(feature = "query")]
 pub struct ReleaseArgs {
     pub id: Id<Release>,
@@ -34,6 +68,9 @@ pub struct ReleaseArgs {
 
 #[cfg(feature = "query")]
 impl Query<ReleaseArgs> for Release {
+    const NAME: &'static str = "release";
+    const CACHED: bool = true;
+
     fn url(args: &ReleaseArgs) -> url::Url {
         ApiUrl::mobile()
             .function("tralbum_details")
@@ -47,7 +84,7 @@ impl Query<ReleaseArgs> for Release {
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(
     feature = "query",
-    derive(Deserialize),
+    derive(Deserialize, Serialize),
     serde(rename_all = "kebab-case")
 )]
 pub enum ReleaseKind {
@@ -74,13 +111,18 @@ impl fmt::Display for ReleaseKind {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-#[cfg_attr(feature = "query", derive(Deserialize))]
+#[cfg_attr(feature = "query", derive(Deserialize, Serialize))]
 pub struct Info {
-    #[cfg_attr(feature = "query", serde(rename = "type"))]
+    pub id: Id<Release>,
+    #[cfg_attr(feature = "query", serde(rename(deserialize = "type")))]
     pub kind: ReleaseKind,
-    #[cfg_attr(feature = "query", serde(rename = "tralbum_artist"))]
+    /// The artist or label whose page the release is on.
+    pub band: Band,
+    #[cfg_attr(feature = "query", serde(rename(deserialize = "tralbum_artist")))]
     pub artist: String,
     pub title: String,
+    #[cfg_attr(feature = "query", serde(default))]
+    pub art_id: Option<Id<Art>>,
     pub about: Option<String>,
     pub credits: Option<String>,
     #[cfg_attr(
@@ -88,16 +130,101 @@ pub struct Info {
         serde(deserialize_with = "Date::deserialize_unix_timestamp")
     )]
     pub release_date: Date,
+    #[cfg_attr(feature = "query", serde(default))]
+    pub tags: Vec<Tag>,
+    /// The name of the label that put out the release, if it's
+    /// not on the label's own page.
+    #[cfg_attr(feature = "query", serde(default))]
+    pub label: Option<String>,
+    #[cfg_attr(feature = "query", serde(default))]
+    pub label_id: Option<Id<Outlet>>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-#[cfg_attr(feature = "query", derive(Deserialize))]
+#[cfg_attr(feature = "query", derive(Deserialize, Serialize))]
+pub struct Band {
+    #[cfg_attr(feature = "query", serde(rename(deserialize = "band_id")))]
+    pub id: Id<Outlet>,
+    #[cfg_attr(feature = "query", serde(default))]
+    pub name: String,
+    #[cfg_attr(feature = "query", serde(default))]
+    pub location: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "query", derive(Deserialize, Serialize))]
+pub struct Tag {
+    pub name: String,
+    /// The name as it appears in links to the tag, e.g. `hip-hop-rap`.
+    #[cfg_attr(feature = "query", serde(rename(deserialize = "norm_name")))]
+    pub normalized: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "query", derive(Deserialize, Serialize))]
 pub struct Track {
+    #[cfg_attr(feature = "query", serde(rename(deserialize = "track_id"), default))]
+    pub id: Option<Id<Track>>,
+    /// The position of the track on the release, starting at 1.
+    #[cfg_attr(feature = "query", serde(default))]
+    pub track_num: Option<u32>,
     pub title: String,
-    #[cfg_attr(feature = "query", serde(rename = "streaming_url"))]
-    pub stream: Stream,
-    #[cfg_attr(feature = "query", serde(deserialize_with = "f32_duration"))]
+    /// The artist of this track, if it differs from the release's.
+    #[cfg_attr(feature = "query", serde(default))]
+    pub artist: Option<String>,
+    #[cfg_attr(
+        feature = "query",
+        serde(
+            deserialize_with = "f32_duration",
+            serialize_with = "serialize_f32_duration"
+        )
+    )]
     pub duration: Duration,
+    #[cfg_attr(feature = "query", serde(default = "common::parse::yes"))]
+    pub is_streamable: bool,
+    #[cfg_attr(feature = "query", serde(default))]
+    pub has_lyrics: bool,
+    #[cfg_attr(feature = "query", serde(default))]
+    pub lyrics: Option<String>,
+    // tables have to come last when serialized to TOML
+    #[cfg_attr(feature = "query", serde(rename = "streaming_url", default))]
+    pub stream: Option<Stream>,
+}
+
+impl Track {
+    /// Returns the stream of the track, unless it can't be streamed.
+    pub fn streamable(&self) -> Option<&Stream> {
+        self.stream.as_ref().filter(|_| self.is_streamable)
+    }
+
+    pub fn set_lyrics(&mut self, lyrics: &Lyrics) {
+        if let Some(text) = self.id.and_then(|id| lyrics.lyrics.get(&id)) {
+            self.lyrics = Some(text.clone())
+        }
+    }
+}
+
+/// The lyrics of the tracks on a release, which
+/// aren't part of the release's details.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "query", derive(Deserialize))]
+pub struct Lyrics {
+    #[cfg_attr(feature = "query", serde(default))]
+    pub lyrics: HashMap<Id<Track>, String>,
+}
+
+#[cfg(feature = "query")]
+impl Query<ReleaseArgs> for Lyrics {
+    const NAME: &'static str = "lyrics";
+    const CACHED: bool = true;
+
+    fn url(args: &ReleaseArgs) -> url::Url {
+        ApiUrl::mobile()
+            .function("tralbum_lyrics")
+            .query("tralbum_id", args.id.to_string())
+            .query("tralbum_type", args.kind.identifier())
+            .into()
+    }
 }
 
 #[cfg(feature = "query")]
@@ -108,13 +235,35 @@ where
     <_>::deserialize(deserializer).map(Duration::from_secs_f32)
 }
 
+#[cfg(feature = "query")]
+pub(super) fn serialize_f32_duration<S>(
+    duration: &Duration,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.serialize_f32(duration.as_secs_f32())
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
-#[cfg_attr(feature = "query", derive(Deserialize))]
+#[cfg_attr(feature = "query", derive(Deserialize, Serialize))]
 #[cfg_attr(feature = "query", serde(rename_all = "kebab-case"))]
 pub struct Stream {
     #[cfg_attr(
         feature = "query",
-        serde(deserialize_with = "common::parse::deserialize_url")
+        serde(
+            deserialize_with = "common::parse::deserialize_url",
+            serialize_with = "common::parse::serialize_url"
+        )
     )]
     pub mp3_128: url::Url,
 }
+
+impl Stream {
+    /// Returns the id of the track that's streamed, which,
+    /// unlike the URL, doesn't change over time.
+    pub fn track_id(&self) -> Option<u64> {
+        self.mp3_128.path_segments()?.next_back()?.parse().ok()
+    }
+}
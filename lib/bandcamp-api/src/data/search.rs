@@ -1,10 +1,11 @@
 #[cfg(feature = "query")]
 use {
     crate::{data::Query, url::ApiUrl},
-    serde::Deserialize,
-    url::Url,
+    serde::{Deserialize, Serialize},
 };
 
+use url::Url;
+
 use crate::data::{
     common::Id,
     fans,
@@ -13,13 +14,116 @@ use crate::data::{
 };
 
 #[derive(Debug)]
-#[cfg_attr(feature = "query", derive(Deserialize))]
+#[cfg_attr(feature = "query", derive(Deserialize, Serialize))]
 pub struct Search {
     pub results: Vec<SearchResult>,
+    /// The arguments of the full search these results are from,
+    /// if they're not from autocompletion.
+    #[cfg_attr(feature = "query", serde(skip))]
+    pub args: Option<SearchArgs>,
+    /// Whether there are more pages of results.
+    #[cfg_attr(feature = "query", serde(skip))]
+    pub more: bool,
+}
+
+impl Search {
+    /// Returns the arguments for the next page of results, if there is one.
+    pub fn next_page(&self) -> Option<SearchArgs> {
+        self.args
+            .as_ref()
+            .filter(|_| self.more)
+            .map(SearchArgs::next_page)
+    }
+}
+
+/// The arguments for a full search, which, unlike autocompletion,
+/// returns results a page at a time and can be restricted to one
+/// kind of result.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchArgs {
+    pub query: String,
+    pub filter: SearchFilter,
+    /// Starts at 1.
+    pub page: u16,
+}
+
+impl SearchArgs {
+    pub fn new(query: impl Into<String>, filter: SearchFilter) -> SearchArgs {
+        SearchArgs {
+            query: query.into(),
+            filter,
+            page: 1,
+        }
+    }
+
+    pub fn next_page(&self) -> SearchArgs {
+        SearchArgs {
+            page: self.page + 1,
+            ..self.clone()
+        }
+    }
+
+    /// Whether these arguments are for a different page of the
+    /// same search as the provided ones.
+    pub fn continues(&self, other: &SearchArgs) -> bool {
+        self.query == other.query && self.filter == other.filter
+    }
+}
+
+/// Which kind of results a search returns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SearchFilter {
+    #[default]
+    All,
+    Outlets,
+    Albums,
+    Tracks,
+    Fans,
+}
+
+impl SearchFilter {
+    pub fn cycle(self) -> SearchFilter {
+        use SearchFilter::*;
+
+        match self {
+            All => Outlets,
+            Outlets => Albums,
+            Albums => Tracks,
+            Tracks => Fans,
+            Fans => All,
+        }
+    }
+
+    /// The identifier Bandcamp uses for the filter.
+    pub fn identifier(self) -> Option<&'static str> {
+        use SearchFilter::*;
+
+        match self {
+            All => None,
+            Outlets => Some("b"),
+            Albums => Some("a"),
+            Tracks => Some("t"),
+            Fans => Some("f"),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        use SearchFilter::*;
+
+        match self {
+            All => "everything",
+            Outlets => "artists & labels",
+            Albums => "albums",
+            Tracks => "tracks",
+            Fans => "fans",
+        }
+    }
 }
 
 #[cfg(feature = "query")]
 impl Query<str> for Search {
+    const NAME: &'static str = "search results";
+
     fn url(q: &str) -> Url {
         ApiUrl::new("fuzzysearch")
             .version("1")
@@ -30,25 +134,28 @@ impl Query<str> for Search {
 }
 
 #[derive(Debug)]
-#[cfg_attr(feature = "query", derive(Deserialize))]
-#[cfg_attr(feature = "query", serde(tag = "type"))]
+#[cfg_attr(feature = "query", derive(Deserialize, Serialize))]
+#[cfg_attr(
+    feature = "query",
+    serde(tag = "type", rename_all(serialize = "kebab-case"))
+)]
 pub enum SearchResult {
-    #[cfg_attr(feature = "query", serde(rename = "b"))]
+    #[cfg_attr(feature = "query", serde(rename(deserialize = "b")))]
     Outlet(Outlet),
-    #[cfg_attr(feature = "query", serde(rename = "a"))]
+    #[cfg_attr(feature = "query", serde(rename(deserialize = "a")))]
     Album(Album),
-    #[cfg_attr(feature = "query", serde(rename = "t"))]
+    #[cfg_attr(feature = "query", serde(rename(deserialize = "t")))]
     Track(Track),
-    #[cfg_attr(feature = "query", serde(rename = "f"))]
+    #[cfg_attr(feature = "query", serde(rename(deserialize = "f")))]
     Fan(Fan),
 }
 
 #[derive(Debug)]
-#[cfg_attr(feature = "query", derive(Deserialize))]
+#[cfg_attr(feature = "query", derive(Deserialize, Serialize))]
 pub struct Outlet {
     #[cfg_attr(
         feature = "query",
-        serde(rename = "is_label", deserialize_with = "outlet_kind")
+        serde(rename(deserialize = "is_label"), deserialize_with = "outlet_kind")
     )]
     pub kind: OutletKind,
     pub name: String,
@@ -68,32 +175,42 @@ where
 }
 
 #[derive(Debug)]
-#[cfg_attr(feature = "query", derive(Deserialize))]
+#[cfg_attr(feature = "query", derive(Deserialize, Serialize))]
 pub struct Album {
     pub name: String,
-    #[cfg_attr(feature = "query", serde(rename = "band_name"))]
+    #[cfg_attr(feature = "query", serde(rename(deserialize = "band_name")))]
     pub artist: String,
     pub id: Id<releases::Release>,
-    #[cfg_attr(feature = "query", serde(rename = "band_id"))]
-    pub artist_id: Id<outlets::Outlet>,
+    /// Full search results only link to the artist.
+    #[cfg_attr(feature = "query", serde(default, rename(deserialize = "band_id")))]
+    pub artist_id: Option<Id<outlets::Outlet>>,
+    /// The page of the album, which full search results provide instead
+    /// of the artist's id.
+    #[cfg_attr(feature = "query", serde(skip))]
+    pub url: Option<Url>,
 }
 
 #[derive(Debug)]
-#[cfg_attr(feature = "query", derive(Deserialize))]
+#[cfg_attr(feature = "query", derive(Deserialize, Serialize))]
 pub struct Track {
     pub name: String,
     pub id: Id<releases::Release>,
-    #[cfg_attr(feature = "query", serde(rename = "album_name"))]
+    #[cfg_attr(feature = "query", serde(rename(deserialize = "album_name")))]
     pub album: Option<String>,
     pub album_id: Option<u64>,
-    #[cfg_attr(feature = "query", serde(rename = "band_name"))]
+    #[cfg_attr(feature = "query", serde(rename(deserialize = "band_name")))]
     pub artist: String,
-    #[cfg_attr(feature = "query", serde(rename = "band_id"))]
-    pub artist_id: Id<outlets::Outlet>,
+    /// Full search results only link to the artist.
+    #[cfg_attr(feature = "query", serde(default, rename(deserialize = "band_id")))]
+    pub artist_id: Option<Id<outlets::Outlet>>,
+    /// The page of the track, which full search results provide instead
+    /// of the artist's id.
+    #[cfg_attr(feature = "query", serde(skip))]
+    pub url: Option<Url>,
 }
 
 #[derive(Debug)]
-#[cfg_attr(feature = "query", derive(Deserialize))]
+#[cfg_attr(feature = "query", derive(Deserialize, Serialize))]
 pub struct Fan {
     pub name: String,
     pub id: Id<fans::Fan>,
@@ -0,0 +1,104 @@
+use {
+    reqwest::StatusCode,
+    snafu::Snafu,
+    std::fmt::{self, Display, Formatter},
+    url::Url,
+};
+
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub(crate)))]
+pub enum Error {
+    #[snafu(display("error requesting {}: {}", endpoint, source))]
+    Transport {
+        source: reqwest::Error,
+        endpoint: Endpoint,
+    },
+    #[snafu(display("bandcamp responded to {} with {}", endpoint, status))]
+    Status {
+        status: StatusCode,
+        endpoint: Endpoint,
+    },
+    /// Bandcamp responded successfully, but reported an error in the response,
+    /// e.g. because what was queried doesn't exist.
+    #[snafu(display("couldn't get the {}: {} ({})", what, message, endpoint))]
+    Bandcamp {
+        what: &'static str,
+        message: String,
+        endpoint: Endpoint,
+    },
+    /// The response doesn't look like what was expected.
+    #[snafu(display("unexpected response from {}: {}", endpoint, source))]
+    Schema {
+        source: serde_json::Error,
+        endpoint: Endpoint,
+    },
+    #[snafu(display("{} isn't cached, so it can't be fetched while offline", endpoint))]
+    Offline { endpoint: Endpoint },
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+impl Error {
+    /// Returns the endpoint of the request that failed.
+    pub fn endpoint(&self) -> &Endpoint {
+        match self {
+            Error::Transport { endpoint, .. }
+            | Error::Status { endpoint, .. }
+            | Error::Bandcamp { endpoint, .. }
+            | Error::Schema { endpoint, .. }
+            | Error::Offline { endpoint } => endpoint,
+        }
+    }
+}
+
+/// The API function a request was sent to, along with its arguments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Endpoint(Box<Url>);
+
+impl Endpoint {
+    /// The name of the function, e.g. `tralbum_details`.
+    pub fn function(&self) -> &str {
+        self.0
+            .path_segments()
+            .and_then(|mut segments| segments.next_back())
+            .unwrap_or_default()
+    }
+
+    pub fn args(&self) -> impl Iterator<Item = (String, String)> + '_ {
+        self.0
+            .query_pairs()
+            .map(|(key, value)| (key.into_owned(), value.into_owned()))
+    }
+
+    pub fn url(&self) -> &Url {
+        &self.0
+    }
+}
+
+impl From<Url> for Endpoint {
+    fn from(url: Url) -> Self {
+        Endpoint(Box::new(url))
+    }
+}
+
+impl From<&Url> for Endpoint {
+    fn from(url: &Url) -> Self {
+        Endpoint(Box::new(url.clone()))
+    }
+}
+
+impl Display for Endpoint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}(", self.function())?;
+
+        for (idx, (key, value)) in self.args().enumerate() {
+            if idx > 0 {
+                f.write_str(", ")?
+            }
+
+            write!(f, "{}={}", key, value)?
+        }
+
+        f.write_str(")")
+    }
+}
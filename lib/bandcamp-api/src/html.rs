@@ -0,0 +1,80 @@
+//! Just enough HTML handling to find things on Bandcamp's pages.
+
+/// Returns the unescaped value of the first attribute with the provided name.
+pub fn attribute(html: &str, name: &str) -> Option<String> {
+    let start = html.find(&format!("{}=\"", name))? + name.len() + 2;
+    let len = html[start..].find('"')?;
+
+    Some(unescape(&html[start..start + len]))
+}
+
+/// Returns the content of the `<meta>` tag with the provided name.
+pub fn meta_content(html: &str, name: &str) -> Option<String> {
+    let start = html.find(&format!("name=\"{}\"", name))?;
+    let end = start + html[start..].find('>')?;
+
+    attribute(&html[start..end], "content")
+}
+
+/// Returns the text inside the first element with the provided class,
+/// without any tags and with its whitespace collapsed.
+/// Elements of the same kind nested inside it aren't supported.
+pub fn text_of_class(html: &str, class: &str) -> Option<String> {
+    let class = html.find(&format!("class=\"{}\"", class))?;
+    let tag_start = html[..class].rfind('<')? + 1;
+    let tag = html[tag_start..]
+        .split(|c: char| c.is_whitespace() || c == '>')
+        .next()?;
+
+    let content = class + html[class..].find('>')? + 1;
+    let len = html[content..].find(&format!("</{}>", tag))?;
+
+    Some(text(&html[content..content + len]))
+}
+
+/// Removes all tags from the provided HTML and collapses its whitespace.
+pub fn text(html: &str) -> String {
+    let mut text = String::new();
+    let mut in_tag = false;
+
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+
+    unescape(&text.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+pub fn unescape(s: &str) -> String {
+    s.replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn class_text() {
+        let html = r#"
+            <div class="result-info">
+                <div class="heading">
+                    <a href="https://a.bandcamp.com">  Tom &amp; <b>Jerry</b> </a>
+                </div>
+            </div>
+        "#;
+
+        assert_eq!(
+            text_of_class(html, "heading").as_deref(),
+            Some("Tom & Jerry")
+        );
+        assert_eq!(text_of_class(html, "subhead"), None);
+    }
+}
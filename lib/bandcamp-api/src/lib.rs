@@ -1,10 +1,41 @@
 pub mod data;
 
+#[cfg(feature = "async")]
+mod async_api;
+
+#[cfg(feature = "query")]
+pub mod cache;
+
+#[cfg(feature = "query")]
+mod error;
+
+#[cfg(feature = "query")]
+mod html;
+
+#[cfg(feature = "query")]
+pub mod resolve;
+
+#[cfg(feature = "query")]
+mod search_page;
+
 #[cfg(feature = "query")]
 mod url;
 
 #[cfg(feature = "query")]
-use {reqwest::blocking::Client, std::marker::PhantomData};
+pub use error::{Endpoint, Error, Result};
+
+#[cfg(feature = "async")]
+pub use async_api::AsyncApi;
+
+#[cfg(feature = "query")]
+use {
+    cache::Cache,
+    error::{Bandcamp, Offline, Schema, Status, Transport},
+    reqwest::blocking::Client,
+    serde::Deserialize,
+    snafu::ResultExt,
+    std::marker::PhantomData,
+};
 
 #[cfg(feature = "query")]
 pub struct Request<T> {
@@ -13,12 +44,26 @@ pub struct Request<T> {
 }
 
 #[cfg(feature = "query")]
-pub type Result<T> = reqwest::Result<T>;
+impl<T> Request<T> {
+    pub fn url(&self) -> &::url::Url {
+        self.inner.url()
+    }
+}
+
+/// A response read from the cache.
+#[cfg(feature = "query")]
+pub struct Cached<T> {
+    pub value: Result<T>,
+    /// Whether the response should be fetched again.
+    pub expired: bool,
+}
 
 #[cfg(feature = "query")]
 #[derive(Clone, Default)]
 pub struct Api {
     client: Client,
+    cache: Option<Cache>,
+    offline: bool,
 }
 
 #[cfg(feature = "query")]
@@ -28,15 +73,49 @@ impl Api {
     }
 
     pub fn with_client(client: Client) -> Api {
-        Api { client }
+        Api {
+            client,
+            ..<_>::default()
+        }
+    }
+
+    /// Stores the responses to cacheable queries in the provided cache.
+    pub fn with_cache(self, cache: Cache) -> Api {
+        Api {
+            cache: cache.into(),
+            ..self
+        }
+    }
+
+    /// Prevents any requests from being sent,
+    /// so that only cached responses are available.
+    pub fn offline(self) -> Api {
+        Api {
+            offline: true,
+            ..self
+        }
     }
 
+    pub fn is_offline(&self) -> bool {
+        self.offline
+    }
+
+    /// Returns the cached response to the query if it hasn't expired
+    /// and can still be parsed, otherwise executes it.
     pub fn query<T, A>(&self, args: &A) -> Result<T>
     where
         T: data::Query<A>,
         A: ?Sized,
     {
-        self.execute(self.request(args))
+        let request = self.request(args);
+
+        match self.cached(&request) {
+            Some(Cached {
+                value: Ok(value),
+                expired: false,
+            }) => Ok(value),
+            _ => self.execute(request),
+        }
     }
 
     pub fn request<T, A>(&self, args: &A) -> Request<T>
@@ -52,15 +131,128 @@ impl Api {
         }
     }
 
+    /// Returns the cached response to the request, if there is one.
+    /// While offline, cached responses never expire,
+    /// since they can't be fetched again anyway.
+    pub fn cached<T, A>(&self, request: &Request<T>) -> Option<Cached<T>>
+    where
+        T: data::Query<A>,
+        A: ?Sized,
+    {
+        if !T::CACHED {
+            return None
+        }
+
+        let entry = self.cache.as_ref()?.get(request.url())?;
+
+        Some(Cached {
+            value: parse::<T, A>(&entry.body, request.url()),
+            expired: entry.expired && !self.offline,
+        })
+    }
+
+    /// Sends the request, storing the response in the cache if there is one.
     pub fn execute<T, A>(&self, request: Request<T>) -> Result<T>
     where
         T: data::Query<A>,
         A: ?Sized,
     {
-        self.client.execute(request.inner)?.json()
+        let url = request.url().clone();
+
+        if self.offline {
+            return Offline { endpoint: url }.fail()
+        }
+
+        let response = self
+            .client
+            .execute(request.inner)
+            .context(Transport { endpoint: &url })?;
+
+        let status = response.status();
+
+        if !status.is_success() {
+            return Status {
+                status,
+                endpoint: url,
+            }
+            .fail()
+        }
+
+        let body = response.bytes().context(Transport { endpoint: &url })?;
+
+        let value = parse::<T, A>(&body, &url)?;
+
+        if let Some(cache) = self.cache.as_ref().filter(|_| T::CACHED) {
+            // not being able to cache the response
+            // shouldn't prevent it from being used
+            let _ = cache.put(&url, &body);
+        }
+
+        Ok(value)
     }
 
     pub fn client(&self) -> &Client {
         &self.client
     }
 }
+
+/// Parses a response, which Bandcamp may use to report an error instead.
+#[cfg(feature = "query")]
+fn parse<T, A>(body: &[u8], url: &::url::Url) -> Result<T>
+where
+    T: data::Query<A>,
+    A: ?Sized,
+{
+    #[derive(Deserialize)]
+    struct Reported {
+        #[serde(default)]
+        error: bool,
+        error_message: Option<String>,
+    }
+
+    if let Ok(Reported {
+        error: true,
+        error_message,
+    }) = serde_json::from_slice(body)
+    {
+        return Bandcamp {
+            what: T::NAME,
+            message: error_message.unwrap_or_else(|| "unknown error".into()),
+            endpoint: url,
+        }
+        .fail()
+    }
+
+    serde_json::from_slice(body).context(Schema { endpoint: url })
+}
+
+#[cfg(all(test, feature = "query"))]
+mod test {
+    use {
+        super::*,
+        data::releases::{Release, ReleaseArgs},
+    };
+
+    #[test]
+    fn reported_errors() {
+        let url = "https://bandcamp.com/api/mobile/24/tralbum_details?tralbum_id=1&band_id=2"
+            .parse()
+            .unwrap();
+
+        let reported = br#"{"error": true, "error_message": "No such tralbum"}"#;
+
+        match parse::<Release, ReleaseArgs>(reported, &url) {
+            Err(e @ Error::Bandcamp { .. }) => assert_eq!(
+                e.to_string(),
+                "couldn't get the release: No such tralbum (tralbum_details(tralbum_id=1, \
+                 band_id=2))"
+            ),
+            other => panic!("parsed as {:?}", other.map(|_| ())),
+        }
+
+        assert!(matches!(
+            parse::<Release, ReleaseArgs>(br#"{"id": 1}"#, &url),
+            Err(Error::Schema { .. })
+        ));
+    }
+}
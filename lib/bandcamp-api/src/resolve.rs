@@ -0,0 +1,245 @@
+//! Turns links to pages on bandcamp.com into the arguments needed to query
+//! them.
+
+use {
+    crate::{
+        data::{
+            common::Id,
+            fans::Fan,
+            outlets::Outlet,
+            releases::{ReleaseArgs, ReleaseKind},
+        },
+        html::{attribute, meta_content},
+        Api,
+    },
+    serde::{de::DeserializeOwned, Deserialize},
+    snafu::{OptionExt, ResultExt, Snafu},
+    url::Url,
+};
+
+#[derive(Debug, Snafu)]
+pub enum ResolveError {
+    #[snafu(display("{} isn't a link to an artist, label, release or fan", url))]
+    Unsupported { url: Url },
+    #[snafu(display("error fetching {}: {}", url, source))]
+    Fetch { source: reqwest::Error, url: Url },
+    #[snafu(display("couldn't find the {} on {}", what, url))]
+    Missing { what: &'static str, url: Url },
+    #[snafu(display("error reading the {} on {}: {}", what, url, source))]
+    Invalid {
+        source: serde_json::Error,
+        what: &'static str,
+        url: Url,
+    },
+}
+
+pub type Result<T, E = ResolveError> = std::result::Result<T, E>;
+
+/// What a resolved link points to.
+#[derive(Debug, Clone, Copy)]
+pub enum Resolved {
+    Outlet(Id<Outlet>),
+    Release(ReleaseArgs),
+    Fan(Id<Fan>),
+}
+
+/// The kinds of pages that can be resolved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Page {
+    Outlet,
+    Release(ReleaseKind),
+    Fan,
+}
+
+impl Page {
+    const DOMAIN: &'static str = "bandcamp.com";
+
+    /// Paths on bandcamp.com itself that aren't fan pages.
+    const RESERVED: &'static [&'static str] = &[
+        "api",
+        "discover",
+        "search",
+        "tag",
+        "tags",
+        "help",
+        "login",
+        "signup",
+        "about",
+        "terms",
+        "privacy",
+        "artists",
+        "labels",
+        "fans",
+        "feed",
+        "settings",
+        "cart",
+        "yum",
+        "gift_cards",
+        "EmbeddedPlayer",
+    ];
+
+    /// Determines which kind of page the provided URL links to,
+    /// without fetching it.
+    pub fn from_url(url: &Url) -> Option<Page> {
+        if !matches!(url.scheme(), "http" | "https") {
+            return None
+        }
+
+        let host = url.host_str()?;
+        let mut segments = url.path_segments()?.filter(|s| !s.is_empty());
+
+        if host == Page::DOMAIN || host == "www.bandcamp.com" {
+            return match (segments.next(), segments.next()) {
+                (Some(name), None) if !Page::RESERVED.contains(&name) => Some(Page::Fan),
+                _ => None,
+            }
+        }
+
+        let subdomain = host.strip_suffix(Page::DOMAIN)?.strip_suffix('.')?;
+
+        if subdomain.is_empty() || subdomain.contains('.') {
+            return None
+        }
+
+        match segments.next() {
+            None | Some("music") | Some("releases") => Some(Page::Outlet),
+            Some("album") => Some(Page::Release(ReleaseKind::Album)),
+            Some("track") => Some(Page::Release(ReleaseKind::Track)),
+            Some(_) => None,
+        }
+    }
+}
+
+impl Api {
+    /// Fetches the page behind the provided link and
+    /// finds out what it shows.
+    pub fn resolve(&self, url: &Url) -> Result<Resolved> {
+        let page = Page::from_url(url).context(Unsupported { url: url.clone() })?;
+
+        let html = self
+            .client()
+            .get(url.clone())
+            .send()
+            .and_then(|response| response.error_for_status())
+            .and_then(|response| response.text())
+            .context(Fetch { url: url.clone() })?;
+
+        resolve_html(page, &html, url)
+    }
+}
+
+fn resolve_html(page: Page, html: &str, url: &Url) -> Result<Resolved> {
+    #[derive(Deserialize)]
+    struct Band {
+        id: Id<Outlet>,
+    }
+
+    #[derive(Deserialize)]
+    struct Properties {
+        item_type: ReleaseKind,
+        item_id: u64,
+    }
+
+    #[derive(Deserialize)]
+    struct PageData {
+        fan_data: FanData,
+    }
+
+    #[derive(Deserialize)]
+    struct FanData {
+        fan_id: Id<Fan>,
+    }
+
+    Ok(match page {
+        Page::Outlet => {
+            let band: Band = parse(attribute(html, "data-band"), "artist info", url)?;
+            Resolved::Outlet(band.id)
+        }
+        Page::Release(_) => {
+            let band: Band = parse(attribute(html, "data-band"), "artist info", url)?;
+            let properties: Properties = parse(
+                meta_content(html, "bc-page-properties"),
+                "release properties",
+                url,
+            )?;
+
+            Resolved::Release(ReleaseArgs {
+                id: Id::new(properties.item_id),
+                kind: properties.item_type,
+                outlet: band.id,
+            })
+        }
+        Page::Fan => {
+            let data: PageData = parse(attribute(html, "data-blob"), "fan info", url)?;
+            Resolved::Fan(data.fan_data.fan_id)
+        }
+    })
+}
+
+/// Parses the JSON found on a page, if there is any.
+fn parse<T: DeserializeOwned>(json: Option<String>, what: &'static str, url: &Url) -> Result<T> {
+    let json = json.context(Missing {
+        what,
+        url: url.clone(),
+    })?;
+
+    serde_json::from_str(&json).context(Invalid {
+        what,
+        url: url.clone(),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn page(url: &str) -> Option<Page> {
+        Page::from_url(&url.parse().unwrap())
+    }
+
+    #[test]
+    fn pages() {
+        assert_eq!(page("https://artist.bandcamp.com"), Some(Page::Outlet));
+        assert_eq!(
+            page("https://artist.bandcamp.com/music"),
+            Some(Page::Outlet)
+        );
+        assert_eq!(
+            page("https://artist.bandcamp.com/album/foo"),
+            Some(Page::Release(ReleaseKind::Album))
+        );
+        assert_eq!(
+            page("http://artist.bandcamp.com/track/foo?from=search"),
+            Some(Page::Release(ReleaseKind::Track))
+        );
+        assert_eq!(page("https://bandcamp.com/somefan"), Some(Page::Fan));
+        assert_eq!(page("https://bandcamp.com/discover"), None);
+        assert_eq!(page("https://bandcamp.com"), None);
+        assert_eq!(page("https://notbandcamp.com/album/foo"), None);
+        assert_eq!(page("https://artist.bandcamp.com/merch"), None);
+        assert_eq!(page("ftp://artist.bandcamp.com"), None);
+    }
+
+    #[test]
+    fn resolve_release() {
+        let html = r#"
+            <meta name="bc-page-properties" content="{&quot;item_type&quot;:&quot;a&quot;,&quot;item_id&quot;:42}">
+            <script data-tralbum="{}" data-band="{&quot;id&quot;:7,&quot;name&quot;:&quot;A &amp; B&quot;}"></script>
+        "#;
+        let url = "https://artist.bandcamp.com/album/foo".parse().unwrap();
+
+        match resolve_html(Page::Release(ReleaseKind::Album), html, &url).unwrap() {
+            Resolved::Release(args) => {
+                assert_eq!(args.id, Id::new(42));
+                assert_eq!(args.kind, ReleaseKind::Album);
+                assert_eq!(args.outlet, Id::new(7));
+            }
+            other => panic!("resolved to {:?}", other),
+        }
+
+        assert!(matches!(
+            resolve_html(Page::Fan, html, &url),
+            Err(ResolveError::Missing { .. })
+        ));
+    }
+}
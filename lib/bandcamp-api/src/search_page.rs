@@ -0,0 +1,215 @@
+//! Full searches, which Bandcamp only offers as a page on its website.
+
+use {
+    crate::{
+        data::{
+            common::Id,
+            outlets::OutletKind,
+            search::{Album, Fan, Outlet, Search, SearchArgs, SearchResult, Track},
+        },
+        error::{Offline, Status, Transport},
+        html,
+        Api,
+        Result,
+    },
+    serde::Deserialize,
+    snafu::ResultExt,
+    url::Url,
+};
+
+impl SearchArgs {
+    fn url(&self) -> Url {
+        let mut url: Url = "https://bandcamp.com/search".parse().unwrap();
+
+        {
+            let mut query = url.query_pairs_mut();
+            query.append_pair("q", &self.query);
+
+            if let Some(filter) = self.filter.identifier() {
+                query.append_pair("item_type", filter);
+            }
+
+            query.append_pair("page", &self.page.to_string());
+        }
+
+        url
+    }
+}
+
+impl Api {
+    /// Fetches a page of the results for a full search.
+    pub fn search(&self, args: &SearchArgs) -> Result<Search> {
+        let url = args.url();
+
+        if self.is_offline() {
+            return Offline { endpoint: url }.fail()
+        }
+
+        let response = self
+            .client()
+            .get(url.clone())
+            .send()
+            .context(Transport { endpoint: &url })?;
+
+        let status = response.status();
+
+        if !status.is_success() {
+            return Status {
+                status,
+                endpoint: url,
+            }
+            .fail()
+        }
+
+        let page = response.text().context(Transport { endpoint: &url })?;
+
+        Ok(parse_page(&page, args.clone()))
+    }
+}
+
+/// Reads the results from a search page, skipping the ones it can't make
+/// sense of, since the page isn't meant to be read by anything but browsers.
+fn parse_page(page: &str, args: SearchArgs) -> Search {
+    let results = page
+        .split("<li class=\"searchresult")
+        .skip(1)
+        .filter_map(parse_result)
+        .collect();
+
+    Search {
+        results,
+        more: page.contains("class=\"next\""),
+        args: Some(args),
+    }
+}
+
+fn parse_result(html: &str) -> Option<SearchResult> {
+    #[derive(Deserialize)]
+    struct Data {
+        id: u64,
+        #[serde(rename = "type")]
+        kind: String,
+    }
+
+    let data: Data = serde_json::from_str(&html::attribute(html, "data-search")?).ok()?;
+    let name = html::text_of_class(html, "heading")?;
+    let subhead = html::text_of_class(html, "subhead").unwrap_or_default();
+    let url = html::text_of_class(html, "itemurl").and_then(|url| url.parse().ok());
+
+    Some(match data.kind.as_str() {
+        "b" => {
+            let kind = match html::text_of_class(html, "itemtype").as_deref() {
+                Some("LABEL") => OutletKind::Label,
+                _ => OutletKind::Artist,
+            };
+
+            SearchResult::Outlet(Outlet {
+                kind,
+                name,
+                id: Id::new(data.id),
+            })
+        }
+        "a" => SearchResult::Album(Album {
+            name,
+            artist: subhead.strip_prefix("by ").unwrap_or(&subhead).into(),
+            id: Id::new(data.id),
+            artist_id: None,
+            url: Some(url?),
+        }),
+        "t" => {
+            let (album, artist) = match subhead.strip_prefix("from ") {
+                Some(rest) => match rest.rsplit_once(" by ") {
+                    Some((album, artist)) => (Some(album.into()), artist.into()),
+                    None => (Some(rest.into()), String::new()),
+                },
+                None => (None, subhead.strip_prefix("by ").unwrap_or(&subhead).into()),
+            };
+
+            SearchResult::Track(Track {
+                name,
+                id: Id::new(data.id),
+                album,
+                album_id: None,
+                artist,
+                artist_id: None,
+                url: Some(url?),
+            })
+        }
+        "f" => SearchResult::Fan(Fan {
+            name,
+            id: Id::new(data.id),
+        }),
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use {super::*, crate::data::search::SearchFilter};
+
+    const PAGE: &str = r#"
+        <ul class="result-items">
+        <li class="searchresult data-search" data-search="{&quot;id&quot;:1,&quot;type&quot;:&quot;b&quot;}">
+            <div class="result-info">
+                <div class="itemtype">LABEL</div>
+                <div class="heading"><a href="https://l.bandcamp.com?from=search">Label</a></div>
+                <div class="subhead">Berlin, Germany</div>
+                <div class="itemurl"><a href="https://l.bandcamp.com?from=search">https://l.bandcamp.com</a></div>
+            </div>
+        </li>
+        <li class="searchresult data-search" data-search="{&quot;id&quot;:2,&quot;type&quot;:&quot;t&quot;}">
+            <div class="result-info">
+                <div class="itemtype">TRACK</div>
+                <div class="heading"><a href="https://a.bandcamp.com/track/t">Song</a></div>
+                <div class="subhead">
+                    from Album by Band
+                </div>
+                <div class="itemurl"><a href="https://a.bandcamp.com/track/t">https://a.bandcamp.com/track/t</a></div>
+            </div>
+        </li>
+        <li class="searchresult data-search" data-search="{&quot;id&quot;:3,&quot;type&quot;:&quot;x&quot;}">
+        </li>
+        </ul>
+        <a class="next" href="/search?q=song&page=2">next</a>
+    "#;
+
+    #[test]
+    fn parse_page() {
+        let args = SearchArgs::new("song", SearchFilter::All);
+        let search = super::parse_page(PAGE, args.clone());
+
+        assert_eq!(search.results.len(), 2);
+        assert!(matches!(
+            &search.results[0],
+            SearchResult::Outlet(Outlet {
+                kind: OutletKind::Label,
+                ..
+            })
+        ));
+
+        match &search.results[1] {
+            SearchResult::Track(track) => {
+                assert_eq!(track.name, "Song");
+                assert_eq!(track.album.as_deref(), Some("Album"));
+                assert_eq!(track.artist, "Band");
+                assert_eq!(
+                    track.url.as_ref().map(Url::as_str),
+                    Some("https://a.bandcamp.com/track/t")
+                );
+            }
+            other => panic!("parsed as {:?}", other),
+        }
+
+        assert_eq!(search.next_page(), Some(args.next_page()));
+    }
+
+    #[test]
+    fn url() {
+        let args = SearchArgs::new("a b", SearchFilter::Albums).next_page();
+
+        assert_eq!(
+            args.url().as_str(),
+            "https://bandcamp.com/search?q=a+b&item_type=a&page=2"
+        );
+    }
+}
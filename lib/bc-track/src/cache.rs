@@ -0,0 +1,425 @@
+use {
+    reqwest::Url,
+    serde::{Deserialize, Serialize},
+    std::{
+        collections::HashMap,
+        fs::{self, File, OpenOptions},
+        io::{self, Read, Seek, SeekFrom, Write},
+        ops::Range,
+        path::{Path, PathBuf},
+        sync::{Arc, Mutex, MutexGuard},
+    },
+};
+
+/// Keeps the parts of tracks that have been downloaded on disk,
+/// so they don't need to be downloaded again when they're replayed
+/// or seeked to. Once the cache grows larger than its capacity,
+/// the least recently used tracks are removed from it.
+#[derive(Clone)]
+pub struct SegmentCache {
+    index: Arc<Mutex<Index>>,
+}
+
+struct Index {
+    dir: PathBuf,
+    capacity: u64,
+    /// Increases every time a track is opened, to determine
+    /// which one was used least recently.
+    clock: u64,
+    /// The number of bytes stored for all tracks.
+    size: u64,
+    /// The number of bytes stored since the index was last saved.
+    unsaved: u64,
+    tracks: HashMap<String, Entry>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct Entry {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    length: Option<u64>,
+    used: u64,
+    ranges: Ranges,
+}
+
+impl SegmentCache {
+    const INDEX: &'static str = "index.toml";
+
+    /// Opens the cache in the provided directory, which holds
+    /// at most `capacity` bytes.
+    pub fn new(dir: impl Into<PathBuf>, capacity: u64) -> io::Result<SegmentCache> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+
+        let mut tracks = read_index(&dir.join(SegmentCache::INDEX))?;
+        let mut stored = HashMap::new();
+
+        // tracks that were stored without the index being saved afterwards,
+        // e.g. because justbc was killed, would never be evicted
+        for file in fs::read_dir(&dir)? {
+            let file = file?;
+            let name = file.file_name().to_string_lossy().into_owned();
+
+            match tracks.remove(&name) {
+                Some(entry) => {
+                    stored.insert(name, entry);
+                }
+                None if name != SegmentCache::INDEX => {
+                    let _ = fs::remove_file(file.path());
+                }
+                None => (),
+            }
+        }
+
+        let clock = stored
+            .values()
+            .map(|entry| entry.used)
+            .max()
+            .unwrap_or_default();
+
+        Ok(SegmentCache {
+            index: Arc::new(Mutex::new(Index {
+                dir,
+                capacity,
+                clock,
+                size: stored.values().map(|entry| entry.ranges.len()).sum(),
+                unsaved: 0,
+                tracks: stored,
+            })),
+        })
+    }
+
+    /// Opens the cached parts of the track at the provided URL.
+    pub(crate) fn open(&self, url: &Url) -> io::Result<Segments> {
+        let key = key(url);
+        let mut index = self.lock();
+
+        index.clock += 1;
+        let used = index.clock;
+
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(index.path(&key))?;
+
+        let entry = index.tracks.entry(key.clone()).or_default();
+        entry.used = used;
+
+        Ok(Segments {
+            cache: self.clone(),
+            key,
+            file,
+        })
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Index> {
+        self.index.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Index {
+    /// How many bytes are stored before the index is saved again,
+    /// so that not too much is unaccounted for if it's never saved.
+    const SAVE_INTERVAL: u64 = 1024 * 1024;
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(key)
+    }
+
+    fn insert(&mut self, key: &str, range: Range<u64>) {
+        // the track may have been evicted by another stream in the meantime
+        let ranges = &mut self.tracks.entry(key.into()).or_default().ranges;
+        let before = ranges.len();
+
+        ranges.insert(range);
+
+        let added = ranges.len() - before;
+        self.size += added;
+        self.unsaved += added;
+    }
+
+    fn remove(&mut self, key: &str) -> Option<Entry> {
+        let entry = self.tracks.remove(key)?;
+        self.size -= entry.ranges.len();
+
+        Some(entry)
+    }
+
+    /// Removes the least recently used tracks until the cache fits its
+    /// capacity again, except for the one with the provided key.
+    /// Returns whether any were removed.
+    fn evict(&mut self, keep: &str) -> bool {
+        let mut evicted = false;
+
+        while self.size > self.capacity {
+            let oldest = self
+                .tracks
+                .iter()
+                .filter(|(key, _)| *key != keep)
+                .min_by_key(|(_, entry)| entry.used)
+                .map(|(key, _)| key.clone());
+
+            match oldest {
+                Some(key) => {
+                    let _ = fs::remove_file(self.path(&key));
+                    self.remove(&key);
+                    evicted = true
+                }
+                None => break,
+            }
+        }
+
+        evicted
+    }
+
+    fn save(&mut self) -> io::Result<()> {
+        let path = self.dir.join(SegmentCache::INDEX);
+        let partial = path.with_extension("part");
+        let toml = toml::to_string(&self.tracks)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        fs::write(&partial, toml)?;
+        fs::rename(partial, path)?;
+
+        self.unsaved = 0;
+
+        Ok(())
+    }
+}
+
+/// Reads an index written by [`Index::save`]. One that can't be
+/// parsed anymore only means the cache starts out empty.
+fn read_index(path: &Path) -> io::Result<HashMap<String, Entry>> {
+    match fs::read_to_string(path) {
+        Ok(toml) => Ok(toml::from_str(&toml).unwrap_or_default()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(<_>::default()),
+        Err(e) => Err(e),
+    }
+}
+
+/// The cached parts of a single track.
+pub(crate) struct Segments {
+    cache: SegmentCache,
+    key: String,
+    file: File,
+}
+
+impl Segments {
+    /// Returns the length of the track if all of it is cached.
+    pub fn complete_length(&self) -> Option<u64> {
+        let index = self.cache.lock();
+        let entry = index.tracks.get(&self.key)?;
+        let length = entry.length?;
+
+        (entry.ranges.end_of(0) == Some(length)).then_some(length)
+    }
+
+    pub fn set_length(&self, length: u64) {
+        if let Some(entry) = self.cache.lock().tracks.get_mut(&self.key) {
+            entry.length = Some(length)
+        }
+    }
+
+    /// Reads from the cached range containing `pos` into `buf`, returning
+    /// `None` if `pos` isn't cached or the cached data can't be read.
+    pub fn read_at(&mut self, pos: u64, buf: &mut [u8]) -> Option<usize> {
+        let end = self
+            .cache
+            .lock()
+            .tracks
+            .get(&self.key)?
+            .ranges
+            .end_of(pos)?;
+        let available = (end - pos).min(buf.len() as u64) as usize;
+
+        let read = self
+            .file
+            .seek(SeekFrom::Start(pos))
+            .and_then(|_| self.file.read(&mut buf[..available]));
+
+        match read {
+            Ok(read) if read > 0 || available == 0 => Some(read),
+            // the file has been tampered with or can't be read,
+            // so the data has to be fetched again
+            _ => {
+                self.cache.lock().remove(&self.key);
+                None
+            }
+        }
+    }
+
+    /// Stores data that starts at `pos` in the track.
+    pub fn write_at(&mut self, pos: u64, data: &[u8]) -> io::Result<()> {
+        if data.is_empty() {
+            return Ok(())
+        }
+
+        self.file.seek(SeekFrom::Start(pos))?;
+        self.file.write_all(data)?;
+
+        let mut index = self.cache.lock();
+
+        index.insert(&self.key, pos..pos + data.len() as u64);
+
+        if index.evict(&self.key) || index.unsaved >= Index::SAVE_INTERVAL {
+            index.save()?
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for Segments {
+    fn drop(&mut self) {
+        let _ = self.cache.lock().save();
+    }
+}
+
+/// Sorted, non-overlapping byte ranges.
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "Vec<Range<u64>>")]
+struct Ranges(Vec<Range<u64>>);
+
+impl From<Vec<Range<u64>>> for Ranges {
+    fn from(ranges: Vec<Range<u64>>) -> Ranges {
+        ranges
+            .into_iter()
+            .fold(<_>::default(), |mut sorted, range| {
+                sorted.insert(range);
+                sorted
+            })
+    }
+}
+
+impl Ranges {
+    fn insert(&mut self, range: Range<u64>) {
+        if range.is_empty() {
+            return
+        }
+
+        let start = self.0.partition_point(|r| r.end < range.start);
+        let end = self.0.partition_point(|r| r.start <= range.end);
+
+        let overlapping = &self.0[start..end];
+
+        let merged = match (overlapping.first(), overlapping.last()) {
+            (Some(first), Some(last)) => first.start.min(range.start)..last.end.max(range.end),
+            _ => range,
+        };
+
+        self.0.splice(start..end, Some(merged));
+    }
+
+    /// Returns the end of the range containing `pos`, if there is one.
+    fn end_of(&self, pos: u64) -> Option<u64> {
+        self.0
+            .iter()
+            .find(|range| range.contains(&pos))
+            .map(|range| range.end)
+    }
+
+    /// The number of bytes covered by all ranges.
+    fn len(&self) -> u64 {
+        self.0.iter().map(|range| range.end - range.start).sum()
+    }
+}
+
+/// Identifies a track by its id, which, unlike the whole URL, doesn't expire.
+fn key(url: &Url) -> String {
+    let id = url
+        .path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .filter(|id| !id.is_empty() && id.bytes().all(|b| b.is_ascii_digit()));
+
+    match id {
+        Some(id) => id.into(),
+        None => format!("{:016x}", fnv1a(url.path())),
+    }
+}
+
+/// A hash that, unlike the standard library's,
+/// stays the same across releases and platforms.
+fn fnv1a(s: &str) -> u64 {
+    const OFFSET: u64 = 0xCBF2_9CE4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01B3;
+
+    s.bytes().fold(OFFSET, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(PRIME)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn ranges() {
+        let mut ranges = Ranges::default();
+
+        ranges.insert(10..20);
+        ranges.insert(30..40);
+        ranges.insert(0..5);
+        assert_eq!(ranges, Ranges(vec![0..5, 10..20, 30..40]));
+
+        ranges.insert(20..25);
+        assert_eq!(ranges, Ranges(vec![0..5, 10..25, 30..40]));
+
+        ranges.insert(4..35);
+        assert_eq!(ranges.0.len(), 1);
+        assert_eq!(ranges.end_of(0), Some(40));
+        assert_eq!(ranges.end_of(39), Some(40));
+        assert_eq!(ranges.end_of(40), None);
+        assert_eq!(ranges.len(), 40);
+    }
+
+    #[test]
+    fn segments() {
+        let dir = std::env::temp_dir().join(format!("bc-track-cache-{}", std::process::id()));
+        let url = |id| {
+            format!("https://t4.bcbits.com/stream/abc/mp3-128/{}?token=x", id)
+                .parse()
+                .unwrap()
+        };
+
+        let cache = SegmentCache::new(&dir, 10).unwrap();
+        let mut first = cache.open(&url(1)).unwrap();
+        let mut buf = [0; 8];
+
+        assert_eq!(first.read_at(0, &mut buf), None);
+
+        first.write_at(2, b"cdef").unwrap();
+        assert_eq!(first.read_at(3, &mut buf), Some(3));
+        assert_eq!(&buf[..3], b"def");
+
+        first.write_at(0, b"ab").unwrap();
+        first.set_length(6);
+        assert_eq!(first.complete_length(), Some(6));
+        drop(first);
+
+        // the first track has to make room for this one
+        let mut second = cache.open(&url(2)).unwrap();
+        second.write_at(0, b"123456").unwrap();
+        drop(second);
+
+        // as if justbc was killed before the index was saved
+        let mut third = cache.open(&url(3)).unwrap();
+        third.write_at(0, b"x").unwrap();
+        std::mem::forget(third);
+
+        let reopened = SegmentCache::new(&dir, 10).unwrap();
+        assert!(reopened.open(&url(1)).unwrap().complete_length().is_none());
+
+        assert!(!dir.join("3").exists());
+
+        let mut second = reopened.open(&url(2)).unwrap();
+        assert_eq!(second.read_at(0, &mut buf), Some(6));
+
+        // data that's gone from the file is read from the network again
+        fs::write(dir.join("2"), b"").unwrap();
+        assert_eq!(second.read_at(0, &mut buf), None);
+        assert_eq!(reopened.lock().size, 0);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
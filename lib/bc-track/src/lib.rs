@@ -1,49 +1,228 @@
+mod cache;
+mod reconnect;
+mod source;
+
+pub use {cache::SegmentCache, reconnect::Reconnect, source::Source};
+
 use {
+    cache::Segments,
     reqwest::{
         blocking::{Client, Response},
         header::{self, HeaderMap},
+        StatusCode,
         Url,
     },
     std::io::{self, Read, Seek},
 };
 
+/// Returns a new URL for a track whose URL has expired, if there is one.
+pub type Refresh = Box<dyn FnMut() -> Option<Url> + Send + Sync>;
+
+/// How a stream recovers from failures while it's being read.
+#[derive(Default)]
+pub struct Recovery {
+    pub reconnect: Reconnect,
+    pub refresh: Option<Refresh>,
+}
+
 /// Allows reading from a Bandcamp track stream and seeking within it.
+/// If the connection drops, the stream reconnects and resumes
+/// where it left off, and if its URL expires, it continues with a new one.
 pub struct TrackStream {
+    /// The URL the stream was created with.
     url: Url,
+    /// The URL that's used instead of `url` once it has expired.
+    refreshed: Option<Url>,
     client: Client,
+    recovery: Recovery,
+    /// The position the next read starts at.
     pos: u64,
     length: Option<u64>,
-    seek: Option<u64>,
-    response: Response,
+    /// The response currently being read from, along with its position.
+    response: Option<(Response, u64)>,
+    segments: Option<Segments>,
+}
+
+/// Why reading from the remote stream failed.
+enum Interrupted {
+    /// The connection dropped or couldn't be established,
+    /// so trying again might work.
+    Dropped(io::Error),
+    /// The URL isn't valid anymore.
+    Expired(io::Error),
+    Failed(io::Error),
+}
+
+impl From<reqwest::Error> for Interrupted {
+    fn from(e: reqwest::Error) -> Self {
+        let status = e.status();
+        let e = io::Error::other(e);
+
+        match status {
+            Some(StatusCode::FORBIDDEN) | Some(StatusCode::GONE) => Interrupted::Expired(e),
+            // other client errors won't go away by asking again
+            Some(status) if !status.is_server_error() => Interrupted::Failed(e),
+            _ => Interrupted::Dropped(e),
+        }
+    }
 }
 
 impl TrackStream {
     /// Creates a new `TrackStream` by fetching a response from the provided URL
     /// using the provided client.
-    pub fn new(url: Url, client: Client) -> reqwest::Result<TrackStream> {
-        client.get(url.clone()).send().map(|response| TrackStream {
+    pub fn new(url: Url, client: Client, recovery: Recovery) -> io::Result<TrackStream> {
+        TrackStream::open(url, client, recovery, None)
+    }
+
+    /// Creates a new `TrackStream` that stores what it downloads in the
+    /// provided cache and reads from it where possible. If the whole track
+    /// is cached already, nothing is fetched at all.
+    pub fn with_cache(
+        url: Url,
+        client: Client,
+        recovery: Recovery,
+        cache: &SegmentCache,
+    ) -> io::Result<TrackStream> {
+        // if the cache can't be used, the track is just streamed
+        let segments = cache.open(&url).ok();
+        TrackStream::open(url, client, recovery, segments)
+    }
+
+    fn open(
+        url: Url,
+        client: Client,
+        recovery: Recovery,
+        segments: Option<Segments>,
+    ) -> io::Result<TrackStream> {
+        let mut stream = TrackStream {
             url,
+            refreshed: None,
             client,
+            recovery,
             pos: 0,
-            length: content_length(response.headers()),
-            seek: None,
-            response,
-        })
+            length: segments.as_ref().and_then(Segments::complete_length),
+            response: None,
+            segments,
+        };
+
+        if stream.length.is_none() {
+            let response = stream.retry(|stream| stream.request(0))?;
+            stream.length = content_length(response.headers());
+
+            if let (Some(segments), Some(length)) = (&stream.segments, stream.length) {
+                segments.set_length(length)
+            }
+
+            stream.response = Some((response, 0));
+        }
+
+        Ok(stream)
+    }
+
+    /// Returns the URL this stream was created with,
+    /// even if it's been replaced since it expired.
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    /// Calls `f` until it succeeds, the error can't be recovered
+    /// from or the reconnect budget is used up.
+    fn retry<T>(
+        &mut self,
+        mut f: impl FnMut(&mut Self) -> Result<T, Interrupted>,
+    ) -> io::Result<T> {
+        let mut backoff = self.recovery.reconnect.start();
+        let mut refreshed = false;
+
+        loop {
+            match f(self) {
+                Ok(t) => return Ok(t),
+                Err(Interrupted::Dropped(e)) => {
+                    self.response = None;
+
+                    if !backoff.wait() {
+                        return Err(e)
+                    }
+                }
+                // if a fresh URL has expired already,
+                // asking for another one won't help
+                Err(Interrupted::Expired(e)) if refreshed => return Err(e),
+                Err(Interrupted::Expired(e)) => {
+                    self.response = None;
+                    refreshed = true;
+
+                    match self.recovery.refresh.as_mut().and_then(|refresh| refresh()) {
+                        Some(url) => self.refreshed = Some(url),
+                        None => return Err(e),
+                    }
+                }
+                Err(Interrupted::Failed(e)) => return Err(e),
+            }
+        }
     }
 
-    fn maybe_seek(&mut self) -> io::Result<()> {
-        if let Some(pos) = self.seek.take() {
-            self.response = self
-                .client
-                .get(self.url.clone())
+    /// Requests the stream starting at `pos`.
+    fn request(&self, pos: u64) -> Result<Response, Interrupted> {
+        let url = self.refreshed.as_ref().unwrap_or(&self.url);
+        let request = self.client.get(url.clone());
+
+        let mut response = if pos == 0 {
+            request.send()
+        } else {
+            request
                 .header(header::RANGE, format!("bytes={}-", pos))
                 .send()
-                .map_err(io::Error::other)?;
+        }
+        .and_then(Response::error_for_status)?;
+
+        if pos > 0 && response.status() != StatusCode::PARTIAL_CONTENT {
+            // the server ignored the range, so
+            // everything before it has to be skipped
+            let skipped = io::copy(&mut (&mut response).take(pos), &mut io::sink())
+                .map_err(Interrupted::Dropped)?;
+
+            if skipped < pos {
+                return Err(Interrupted::Failed(io::ErrorKind::UnexpectedEof.into()))
+            }
+        }
+
+        Ok(response)
+    }
+
+    fn read_remote(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.retry(|stream| stream.try_read_remote(buf))?;
+
+        if let Some(segments) = &mut self.segments {
+            // failing to cache the data doesn't stop it from being played
+            let _ = segments.write_at(self.pos, &buf[..read]);
+        }
+
+        Ok(read)
+    }
+
+    fn try_read_remote(&mut self, buf: &mut [u8]) -> Result<usize, Interrupted> {
+        let (response, pos) = match &mut self.response {
+            Some((response, pos)) if *pos == self.pos => (response, pos),
+            _ => {
+                let response = self.request(self.pos)?;
+                let (response, pos) = self.response.insert((response, self.pos));
+                (response, pos)
+            }
+        };
+
+        let read = response.read(buf).map_err(Interrupted::Dropped)?;
+        *pos += read as u64;
+
+        let truncated = read == 0 && !buf.is_empty() && self.length.is_some_and(|len| *pos < len);
 
-            self.pos = pos;
+        if truncated {
+            return Err(Interrupted::Dropped(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "stream ended early",
+            )))
         }
 
-        Ok(())
+        Ok(read)
     }
 }
 
@@ -58,8 +237,23 @@ fn content_length(headers: &HeaderMap) -> Option<u64> {
 
 impl Read for TrackStream {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
-        self.maybe_seek()?;
-        self.response.read(buf)
+        if self.length.is_some_and(|len| self.pos >= len) {
+            return Ok(0)
+        }
+
+        let cached = match &mut self.segments {
+            Some(segments) => segments.read_at(self.pos, buf),
+            None => None,
+        };
+
+        let read = match cached {
+            Some(read) => read,
+            None => self.read_remote(buf)?,
+        };
+
+        self.pos += read as u64;
+
+        Ok(read)
     }
 }
 
@@ -78,9 +272,7 @@ impl Seek for TrackStream {
                 .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, LEN_UNKNOWN))?,
         };
 
-        let pos = self.seek.unwrap_or_default() + pos;
-
-        self.seek = if self.pos == pos { None } else { Some(pos) };
+        self.pos = pos;
 
         Ok(pos)
     }
@@ -93,3 +285,183 @@ fn offset(pos: u64, offs: i64) -> u64 {
         pos + offs as u64
     }
 }
+
+#[cfg(test)]
+mod test {
+    use {
+        super::*,
+        std::{
+            io::{BufRead, BufReader, Write},
+            net::TcpListener,
+            sync::{
+                atomic::{AtomicUsize, Ordering},
+                Arc,
+            },
+            thread,
+            time::Duration,
+        },
+    };
+
+    const TRACK: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+    /// Serves `TRACK`, honouring range requests, and counts the requests.
+    /// The first `drops` responses are cut off halfway through, and
+    /// URLs with an `expired` query can't be used to resume the track.
+    fn serve(drops: usize) -> (Url, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!(
+            "http://{}/stream/mp3-128/42",
+            listener.local_addr().unwrap()
+        );
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut lines = BufReader::new(&stream).lines().map(Result::unwrap);
+                let expired = lines.next().unwrap().contains("expired");
+                let start = lines
+                    .take_while(|line| !line.is_empty())
+                    .find_map(|line| {
+                        line.to_lowercase()
+                            .strip_prefix("range: bytes=")?
+                            .trim_end_matches('-')
+                            .parse()
+                            .ok()
+                    })
+                    .unwrap_or(0);
+
+                let served = counter.fetch_add(1, Ordering::SeqCst);
+
+                if expired && start > 0 {
+                    let _ = write!(
+                        stream,
+                        "HTTP/1.1 403 Forbidden\r\nContent-Length: 0\r\n\r\n"
+                    );
+                    continue
+                }
+
+                let body = &TRACK[start..];
+                let status = if start > 0 {
+                    "206 Partial Content"
+                } else {
+                    "200 OK"
+                };
+
+                write!(
+                    stream,
+                    "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    status,
+                    body.len()
+                )
+                .unwrap();
+
+                if served < drops {
+                    let _ = stream.write_all(&body[..body.len() / 2]);
+                } else {
+                    let _ = stream.write_all(body);
+                }
+            }
+        });
+
+        (url.parse().unwrap(), requests)
+    }
+
+    #[test]
+    fn cached_reads() {
+        let (url, requests) = serve(0);
+        let dir = std::env::temp_dir().join(format!("bc-track-stream-{}", std::process::id()));
+        let cache = SegmentCache::new(&dir, 1 << 20).unwrap();
+        let client = Client::new();
+
+        let mut stream =
+            TrackStream::with_cache(url.clone(), client.clone(), <_>::default(), &cache).unwrap();
+        let mut head = [0; 10];
+        stream.read_exact(&mut head).unwrap();
+        assert_eq!(&head, b"0123456789");
+
+        stream.seek(io::SeekFrom::Start(20)).unwrap();
+        let mut tail = Vec::new();
+        stream.read_to_end(&mut tail).unwrap();
+        assert_eq!(tail, &TRACK[20..]);
+
+        // seeking back into what has been read is served from the cache
+        stream.seek(io::SeekFrom::Start(2)).unwrap();
+        stream.read_exact(&mut head[..5]).unwrap();
+        assert_eq!(&head[..5], b"23456");
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+
+        let mut all = Vec::new();
+        stream.seek(io::SeekFrom::Start(0)).unwrap();
+        stream.read_to_end(&mut all).unwrap();
+        assert_eq!(all, TRACK);
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+        drop(stream);
+
+        // now that it's complete, replaying it doesn't need the network
+        let mut replay = TrackStream::with_cache(url, client, <_>::default(), &cache).unwrap();
+        all.clear();
+        replay.read_to_end(&mut all).unwrap();
+        assert_eq!(all, TRACK);
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn resumes() {
+        let reconnect = Reconnect {
+            delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(4),
+            budget: Duration::from_secs(5),
+        };
+
+        let (url, requests) = serve(3);
+        let recovery = Recovery {
+            reconnect,
+            refresh: None,
+        };
+
+        let mut stream = TrackStream::new(url, Client::new(), recovery).unwrap();
+        let mut all = Vec::new();
+        stream.read_to_end(&mut all).unwrap();
+
+        assert_eq!(all, TRACK);
+        assert_eq!(requests.load(Ordering::SeqCst), 4);
+
+        let (url, _) = serve(usize::MAX);
+        let recovery = Recovery {
+            reconnect: Reconnect::NEVER,
+            refresh: None,
+        };
+
+        let mut stream = TrackStream::new(url, Client::new(), recovery).unwrap();
+        all.clear();
+
+        assert!(stream.read_to_end(&mut all).is_err());
+        assert_eq!(all, &TRACK[..TRACK.len() / 2]);
+    }
+
+    #[test]
+    fn refreshes_expired() {
+        let (url, _) = serve(0);
+        let mut expired = url.clone();
+        expired.set_query(Some("expired"));
+
+        let recovery = Recovery {
+            reconnect: Reconnect::NEVER,
+            refresh: Some(Box::new(move || Some(url.clone()))),
+        };
+
+        let mut stream = TrackStream::new(expired.clone(), Client::new(), recovery).unwrap();
+        let mut buf = [0; 4];
+        stream.read_exact(&mut buf).unwrap();
+
+        stream.seek(io::SeekFrom::Start(20)).unwrap();
+        stream.read_exact(&mut buf).unwrap();
+
+        assert_eq!(&buf, b"klmn");
+        assert_eq!(stream.url(), &expired);
+    }
+}
@@ -0,0 +1,76 @@
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+/// How a stream tries to reconnect after its connection drops.
+/// The delay between attempts doubles every time one fails,
+/// until the stream gives up once its budget is used up.
+#[derive(Debug, Clone, Copy)]
+pub struct Reconnect {
+    /// The delay before the first attempt.
+    pub delay: Duration,
+    /// The longest delay between two attempts.
+    pub max_delay: Duration,
+    /// How long to keep trying before giving up.
+    pub budget: Duration,
+}
+
+impl Reconnect {
+    /// Doesn't try to reconnect at all.
+    pub const NEVER: Reconnect = Reconnect {
+        delay: Duration::ZERO,
+        max_delay: Duration::ZERO,
+        budget: Duration::ZERO,
+    };
+
+    /// Keeps trying for the provided duration, using the default delays.
+    pub fn within(budget: Duration) -> Reconnect {
+        Reconnect {
+            budget,
+            ..<_>::default()
+        }
+    }
+
+    pub(crate) fn start(&self) -> Backoff {
+        Backoff {
+            delay: self.delay,
+            max_delay: self.max_delay,
+            deadline: Instant::now() + self.budget,
+        }
+    }
+}
+
+impl Default for Reconnect {
+    fn default() -> Self {
+        Reconnect {
+            delay: Duration::from_millis(250),
+            max_delay: Duration::from_secs(8),
+            budget: Duration::from_secs(30),
+        }
+    }
+}
+
+/// The attempts to reconnect after a single failure.
+pub(crate) struct Backoff {
+    delay: Duration,
+    max_delay: Duration,
+    deadline: Instant,
+}
+
+impl Backoff {
+    /// Waits before the next attempt, returning `false`
+    /// if there's no budget left to make one.
+    pub fn wait(&mut self) -> bool {
+        let remaining = self.deadline.saturating_duration_since(Instant::now());
+
+        if remaining.is_zero() {
+            return false
+        }
+
+        thread::sleep(self.delay.min(remaining));
+        self.delay = (self.delay * 2).min(self.max_delay);
+
+        true
+    }
+}
@@ -0,0 +1,68 @@
+use {
+    crate::{Recovery, SegmentCache, TrackStream},
+    reqwest::{blocking::Client, Url},
+    std::{
+        fs::File,
+        io::{self, Read, Seek},
+        path::Path,
+    },
+};
+
+/// Where the audio of a track comes from, either
+/// streamed from Bandcamp or read from a local file.
+pub enum Source {
+    Remote(Box<TrackStream>),
+    Local {
+        /// The URL the track would otherwise be streamed from.
+        url: Url,
+        file: File,
+    },
+}
+
+impl Source {
+    /// Starts streaming the track at the provided URL,
+    /// using the cache if one is provided.
+    pub fn fetch(
+        url: Url,
+        client: Client,
+        recovery: Recovery,
+        cache: Option<&SegmentCache>,
+    ) -> io::Result<Source> {
+        match cache {
+            Some(cache) => TrackStream::with_cache(url, client, recovery, cache),
+            None => TrackStream::new(url, client, recovery),
+        }
+        .map(|stream| Source::Remote(Box::new(stream)))
+    }
+
+    /// Opens a local copy of the track at the provided URL.
+    pub fn open(url: Url, path: impl AsRef<Path>) -> io::Result<Source> {
+        File::open(path).map(|file| Source::Local { url, file })
+    }
+
+    /// Returns the URL the track is or would be streamed from.
+    pub fn url(&self) -> &Url {
+        match self {
+            Source::Remote(stream) => stream.url(),
+            Source::Local { url, .. } => url,
+        }
+    }
+}
+
+impl Read for Source {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Source::Remote(stream) => stream.read(buf),
+            Source::Local { file, .. } => file.read(buf),
+        }
+    }
+}
+
+impl Seek for Source {
+    fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
+        match self {
+            Source::Remote(stream) => stream.seek(pos),
+            Source::Local { file, .. } => file.seek(pos),
+        }
+    }
+}
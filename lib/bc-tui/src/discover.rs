@@ -0,0 +1,110 @@
+use {
+    crate::symbols,
+    bandcamp_api::data::{
+        discover::{Discover, Discovered},
+        releases::ReleaseKind,
+    },
+    builder::builder_methods,
+    gen_tui::{layout::RectExt, style::StyleExt},
+    tui::{
+        buffer::Buffer,
+        layout::Rect,
+        style::Style,
+        text::Span,
+        widgets::{List, ListItem, ListState, StatefulWidget},
+    },
+};
+
+pub struct DiscoverView<'a> {
+    discover: &'a Discover,
+    style: Style,
+    highlight_style: Style,
+}
+
+impl<'a> DiscoverView<'a> {
+    pub fn new(discover: &'a Discover) -> DiscoverView<'a> {
+        DiscoverView {
+            discover,
+            style: <_>::default(),
+            highlight_style: <_>::default(),
+        }
+    }
+
+    builder_methods! {
+        pub style: Style;
+        pub highlight_style: Style
+    }
+
+    fn title(&self) -> String {
+        let args = match &self.discover.args {
+            Some(args) => args,
+            None => return "Discover".into(),
+        };
+
+        let genre = args.genre.as_deref().unwrap_or("all genres");
+
+        match &args.subgenre {
+            Some(subgenre) => format!("Discover {} / {}", genre, subgenre),
+            None => format!("Discover {}", genre),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct DiscoverViewState {
+    pub releases: ListState,
+    loading: bool,
+}
+
+impl DiscoverViewState {
+    pub fn set_loading(&mut self, loading: bool) {
+        self.loading = loading
+    }
+}
+
+impl<'a> StatefulWidget for DiscoverView<'a> {
+    type State = DiscoverViewState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let title = Span::styled(
+            format!("{} {}", symbols::DISCOVER, self.title()),
+            self.style.bold(),
+        );
+
+        buf.set_span(area.x, area.y, &title, area.width);
+
+        let last = if state.loading {
+            "Loading more..."
+        } else {
+            "Load more..."
+        };
+
+        let more = self.discover.next_page().is_some();
+
+        let items = self
+            .discover
+            .items
+            .iter()
+            .map(|discovered| ListItem::new(fmt_discovered(discovered)))
+            .chain(more.then(|| ListItem::new(last)))
+            .collect::<Vec<_>>();
+
+        List::new(items)
+            .style(self.style)
+            .highlight_style(self.highlight_style)
+            .render(area.shrink_top(2), buf, &mut state.releases)
+    }
+}
+
+fn fmt_discovered(discovered: &Discovered) -> String {
+    let icon = match discovered.kind {
+        ReleaseKind::Album => symbols::ALBUM,
+        ReleaseKind::Track => symbols::TRACK,
+    };
+
+    format!(
+        "{} {}",
+        icon,
+        crate::release_fmt(&discovered.artist, &discovered.title)
+    )
+}
@@ -1,6 +1,9 @@
+mod discover;
 mod fans;
+mod library;
 pub mod nav;
 mod outlets;
+pub mod queue;
 pub mod releases;
 mod search;
 mod symbols;
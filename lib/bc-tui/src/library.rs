@@ -0,0 +1,86 @@
+use {
+    crate::symbols,
+    bandcamp_api::data::{outlets::OutletKind, releases::ReleaseKind},
+    builder::builder_methods,
+    library::Entry,
+    tui::{
+        buffer::Buffer,
+        layout::Rect,
+        style::Style,
+        text::Span,
+        widgets::{List, ListItem, ListState, StatefulWidget},
+    },
+};
+
+pub struct LibraryView<'a> {
+    entries: Vec<&'a Entry>,
+    style: Style,
+    highlight_style: Style,
+}
+
+impl<'a> LibraryView<'a> {
+    pub fn new(entries: impl IntoIterator<Item = &'a Entry>) -> LibraryView<'a> {
+        LibraryView {
+            entries: entries.into_iter().collect(),
+            style: <_>::default(),
+            highlight_style: <_>::default(),
+        }
+    }
+
+    builder_methods! {
+        pub style: Style;
+        pub highlight_style: Style
+    }
+}
+
+pub type LibraryViewState = ListState;
+
+impl<'a> StatefulWidget for LibraryView<'a> {
+    type State = LibraryViewState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        if self.entries.is_empty() {
+            buf.set_span(
+                area.x,
+                area.y,
+                &Span::styled("Nothing saved", self.style),
+                area.width,
+            );
+            return
+        }
+
+        let items = self
+            .entries
+            .into_iter()
+            .map(fmt_entry)
+            .map(ListItem::new)
+            .collect::<Vec<_>>();
+
+        List::new(items)
+            .style(self.style)
+            .highlight_style(self.highlight_style)
+            .render(area, buf, state)
+    }
+}
+
+fn fmt_entry(entry: &Entry) -> String {
+    match entry {
+        Entry::Release(release) => format!(
+            "{} {}",
+            match release.kind {
+                ReleaseKind::Album => symbols::ALBUM,
+                ReleaseKind::Track => symbols::TRACK,
+            },
+            crate::release_fmt(&release.artist, &release.title)
+        ),
+        Entry::Outlet(outlet) => format!(
+            "{} {}",
+            match outlet.kind {
+                OutletKind::Artist => symbols::ARTIST,
+                OutletKind::Label => symbols::LABEL,
+            },
+            outlet.name
+        ),
+        Entry::Fan(fan) => format!("{} {}", symbols::FAN, fan.name),
+    }
+}
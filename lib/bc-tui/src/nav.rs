@@ -1,12 +1,19 @@
 use {
-    super::{fans::*, outlets::*, releases::*, search::*},
-    bandcamp_api::data::{fans::Fan, outlets::*, releases::Release, search::Search},
+    super::{discover::*, fans::*, library::*, outlets::*, queue::*, releases::*, search::*},
+    bandcamp_api::data::{
+        discover::Discover,
+        fans::Fan,
+        outlets::*,
+        releases::Release,
+        search::Search,
+    },
     builder::builder_methods,
     gen_tui::{
         layout::RectExt,
         style::StyleExt,
         widgets::{Spinner, SpinnerState, StatefulWidgetExt, TextInput, TextInputState},
     },
+    library::Library,
     tui::{
         buffer::Buffer,
         layout::Rect,
@@ -16,11 +23,17 @@ use {
     },
 };
 
-#[derive(Copy, Clone, Default)]
+#[derive(Copy, Clone)]
 enum Show<'a> {
-    #[default]
-    Library,
+    Library(&'a Library, &'a str),
     Explore(Explore<'a>),
+    Queue(&'a [QueueItem<'a>], Option<usize>),
+}
+
+impl<'a> Default for Show<'a> {
+    fn default() -> Self {
+        Show::Explore(Explore::Blank)
+    }
 }
 
 #[derive(Copy, Clone)]
@@ -29,6 +42,7 @@ enum Explore<'a> {
     Loading,
     Fan(&'a Fan),
     Search(&'a Search),
+    Discover(&'a Discover),
     Outlet(&'a Outlet),
     Release(&'a Release),
 }
@@ -36,14 +50,24 @@ enum Explore<'a> {
 #[derive(Default)]
 pub struct NavView<'a> {
     show: Show<'a>,
+    search_hint: Option<&'a str>,
     style: Style,
     highlight_style: Style,
 }
 
 impl<'a> NavView<'a> {
-    pub fn library(self) -> Self {
+    /// Shows the entries of the library that match the provided filter.
+    pub fn library(self, library: &'a Library, filter: &'a str) -> Self {
         Self {
-            show: Show::Library,
+            show: Show::Library(library, filter),
+            ..self
+        }
+    }
+
+    /// Shows the provided queue, highlighting the track that's playing.
+    pub fn queue(self, items: &'a [QueueItem<'a>], playing: Option<usize>) -> Self {
+        Self {
+            show: Show::Queue(items, playing),
             ..self
         }
     }
@@ -76,6 +100,13 @@ impl<'a> NavView<'a> {
         }
     }
 
+    pub fn discover(self, discover: &'a Discover) -> Self {
+        Self {
+            show: Show::Explore(Explore::Discover(discover)),
+            ..self
+        }
+    }
+
     pub fn outlet(self, outlet: &'a Outlet) -> Self {
         Self {
             show: Show::Explore(Explore::Outlet(outlet)),
@@ -91,16 +122,19 @@ impl<'a> NavView<'a> {
     }
 
     builder_methods! {
+        /// Text shown at the end of the search bar, e.g. what's searched for.
+        pub search_hint: Option<&'a str>;
         pub style: Style;
         pub highlight_style: Style
     }
 
-    const TABS: [&'static str; 2] = ["Library", "Explore"];
+    const TABS: [&'static str; 3] = ["Library", "Explore", "Queue"];
 
     fn draw_tabs(&self, area: Rect, buf: &mut Buffer) -> Rect {
         use tui::widgets::Widget;
 
-        let width = Self::TABS.iter().copied().map(str::len).sum::<usize>() + 3;
+        let width =
+            Self::TABS.iter().copied().map(str::len).sum::<usize>() + 3 * (Self::TABS.len() - 1);
 
         let center_offset = (width / 2 + 1) as u16;
 
@@ -110,7 +144,7 @@ impl<'a> NavView<'a> {
             .grow_right(center_offset)
             .scale_from_top(1);
 
-        let titles = ["Library", "Explore"]
+        let titles = Self::TABS
             .iter()
             .cloned()
             .map(Span::raw)
@@ -118,8 +152,9 @@ impl<'a> NavView<'a> {
             .collect();
 
         let selected = match self.show {
-            Show::Library => 0,
+            Show::Library(..) => 0,
             Show::Explore(_) => 1,
+            Show::Queue(..) => 2,
         };
 
         Tabs::new(titles)
@@ -133,10 +168,27 @@ impl<'a> NavView<'a> {
     }
 
     fn draw_search_bar(&self, area: Rect, buf: &mut Buffer, state: &mut NavViewState) -> Rect {
-        TextInput::default()
-            .prompt("🔍")
-            .style(self.style)
-            .render(area, buf, &mut state.input);
+        let mut input_area = area;
+
+        if let Some(hint) = self.search_hint {
+            let hint = format!("[{}]", hint);
+            let width = (hint.chars().count() as u16).min(area.width);
+            input_area = area.shrink_right(width + 1);
+
+            buf.set_stringn(
+                area.right() - width,
+                area.y,
+                hint,
+                width as usize,
+                self.style.dim(),
+            );
+        }
+
+        TextInput::default().prompt("🔍").style(self.style).render(
+            input_area,
+            buf,
+            &mut state.input,
+        );
 
         area.shrink_top(2)
     }
@@ -149,12 +201,27 @@ impl<'a> NavView<'a> {
         }
 
         match self.show {
-            Show::Library => todo!(),
+            Show::Library(library, filter) => self
+                .draw_library(library, filter)
+                .scrollable()
+                .scroll_y(state.scroll)
+                .render(area, buf, state.library()),
+            Show::Queue(items, playing) => QueueView::new(items)
+                .playing(playing)
+                .style(self.style)
+                .highlight_style(self.highlight_style)
+                .playing_style(self.highlight_style)
+                .scrollable()
+                .scroll_y(state.scroll)
+                .render(area, buf, state.queue()),
             Show::Explore(xp) => match xp {
                 Explore::Blank => state.blank(),
                 Explore::Loading => self.draw_loading(area, buf, state.spinner()),
                 Explore::Fan(fan) => draw!(fan, Self::draw_fan, NavViewState::fan),
                 Explore::Search(search) => draw!(search, Self::draw_search, NavViewState::results),
+                Explore::Discover(discover) => {
+                    draw!(discover, Self::draw_discover, NavViewState::discover)
+                }
                 Explore::Outlet(outlet) => draw!(outlet, Self::draw_outlet, NavViewState::outlet),
                 Explore::Release(release) => {
                     draw!(release, Self::draw_release, NavViewState::release)
@@ -186,6 +253,12 @@ impl<'a> NavView<'a> {
         Spinner::default().render(area.centered(4, 2), buf, state)
     }
 
+    fn draw_library<'l>(&self, library: &'l Library, filter: &str) -> LibraryView<'l> {
+        LibraryView::new(library.filtered(filter))
+            .style(self.style)
+            .highlight_style(self.highlight_style)
+    }
+
     fn draw_fan<'f>(&self, fan: &'f Fan) -> FanView<'f> {
         FanView::new(fan)
             .style(self.style)
@@ -194,6 +267,13 @@ impl<'a> NavView<'a> {
 
     fn draw_search<'s>(&self, search: &'s Search) -> ResultList<'s> {
         ResultList::new(&search.results)
+            .more(search.more)
+            .style(self.style)
+            .highlight_style(self.highlight_style)
+    }
+
+    fn draw_discover<'d>(&self, discover: &'d Discover) -> DiscoverView<'d> {
+        DiscoverView::new(discover)
             .style(self.style)
             .highlight_style(self.highlight_style)
     }
@@ -215,8 +295,11 @@ enum BodyState {
     Blank,
     Fan(FanViewState),
     Results(ResultListState),
+    Discover(DiscoverViewState),
     Release(ReleaseViewState),
     Outlet(OutletViewState),
+    Library(LibraryViewState),
+    Queue(QueueViewState),
     Spinner(SpinnerState),
 }
 
@@ -248,9 +331,12 @@ impl NavViewState {
         match &mut self.body {
             BodyState::Blank | BodyState::Spinner(_) => return None,
             BodyState::Outlet(o) => o,
+            BodyState::Library(l) => l,
+            BodyState::Queue(q) => q,
             BodyState::Release(r) => &mut *r,
             BodyState::Results(r) => r,
             BodyState::Fan(f) => &mut f.collection,
+            BodyState::Discover(d) => &mut d.releases,
         }
         .into()
     }
@@ -259,9 +345,12 @@ impl NavViewState {
         match &self.body {
             BodyState::Blank | BodyState::Spinner(_) => return None,
             BodyState::Outlet(o) => o,
+            BodyState::Library(l) => l,
+            BodyState::Queue(q) => q,
             BodyState::Release(r) => r,
             BodyState::Results(r) => r,
             BodyState::Fan(f) => &f.collection,
+            BodyState::Discover(d) => &d.releases,
         }
         .into()
     }
@@ -309,6 +398,10 @@ impl NavViewState {
         get_body!(self.body, Results)
     }
 
+    pub fn discover(&mut self) -> &mut DiscoverViewState {
+        get_body!(self.body, Discover)
+    }
+
     pub fn release(&mut self) -> &mut ReleaseViewState {
         get_body!(self.body, Release)
     }
@@ -316,6 +409,14 @@ impl NavViewState {
     pub fn outlet(&mut self) -> &mut OutletViewState {
         get_body!(self.body, Outlet)
     }
+
+    pub fn library(&mut self) -> &mut LibraryViewState {
+        get_body!(self.body, Library)
+    }
+
+    pub fn queue(&mut self) -> &mut QueueViewState {
+        get_body!(self.body, Queue)
+    }
 }
 
 impl<'a> StatefulWidget for NavView<'a> {
@@ -0,0 +1,93 @@
+use {
+    crate::releases::timed_text,
+    bandcamp_api::data::releases::Track,
+    builder::builder_methods,
+    gen_tui::style::StyleExt,
+    tui::{
+        buffer::Buffer,
+        layout::Rect,
+        style::Style,
+        text::Span,
+        widgets::{List, ListItem, ListState, StatefulWidget},
+    },
+};
+
+/// A track in a [QueueView](QueueView), along with its artist.
+#[derive(Copy, Clone)]
+pub struct QueueItem<'a> {
+    pub artist: &'a str,
+    pub track: &'a Track,
+    /// Whether the track was explicitly queued by the user.
+    pub queued: bool,
+}
+
+pub struct QueueView<'a> {
+    items: &'a [QueueItem<'a>],
+    playing: Option<usize>,
+    style: Style,
+    highlight_style: Style,
+    playing_style: Style,
+}
+
+impl<'a> QueueView<'a> {
+    pub fn new(items: &'a [QueueItem<'a>]) -> QueueView<'a> {
+        QueueView {
+            items,
+            playing: None,
+            style: <_>::default(),
+            highlight_style: <_>::default(),
+            playing_style: <_>::default(),
+        }
+    }
+
+    builder_methods! {
+        /// Sets the index of the track that's currently playing.
+        pub playing: Option<usize>;
+        pub style: Style;
+        pub highlight_style: Style;
+        pub playing_style: Style
+    }
+
+    fn item(&self, index: usize, item: &QueueItem<'a>, width: u16) -> ListItem<'a> {
+        let style = match self.playing {
+            Some(playing) if playing == index => self.playing_style,
+            Some(playing) if playing > index => self.style.dim(),
+            _ if item.queued => self.style.italic(),
+            _ => self.style,
+        };
+
+        let title = crate::fmt_release(item.artist, &item.track.title);
+
+        ListItem::new(timed_text(title, item.track.duration, width, style))
+    }
+}
+
+pub type QueueViewState = ListState;
+
+impl<'a> StatefulWidget for QueueView<'a> {
+    type State = QueueViewState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        if self.items.is_empty() {
+            buf.set_span(
+                area.x,
+                area.y,
+                &Span::styled("Nothing queued", self.style),
+                area.width,
+            );
+            return
+        }
+
+        let items = self
+            .items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| self.item(index, item, area.width))
+            .collect::<Vec<_>>();
+
+        List::new(items)
+            .style(self.style)
+            .highlight_style(self.highlight_style)
+            .render(area, buf, state)
+    }
+}
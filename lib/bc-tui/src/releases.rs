@@ -2,8 +2,12 @@ use {
     crate::tracks,
     bandcamp_api::data::releases::{Release, Track},
     builder::builder_methods,
-    gen_tui::{layout::RectExt, style::StyleExt},
-    std::time::Duration,
+    gen_tui::{
+        layout::RectExt,
+        style::StyleExt,
+        widgets::{Image, Pixels},
+    },
+    std::{borrow::Cow, time::Duration},
     tui::{
         buffer::Buffer,
         layout::Rect,
@@ -15,24 +19,55 @@ use {
 
 pub struct ReleaseView<'a> {
     release: &'a Release,
+    art: Option<&'a Pixels>,
     style: Style,
     playing_style: Style,
 }
 
 impl<'a> ReleaseView<'a> {
+    pub const ART_WIDTH: u16 = 32;
+
     pub fn new(release: &'a Release) -> Self {
         Self {
             release,
+            art: None,
             style: <_>::default(),
             playing_style: <_>::default(),
         }
     }
 
     builder_methods! {
+        pub art: impl Into<Option<&'a Pixels>> => art.into();
         pub style: Style;
         pub playing_style: Style
     }
 
+    /// Draws the cover art, if there is any, as a square that's
+    /// at most [`ART_WIDTH`](Self::ART_WIDTH) columns wide.
+    fn draw_art(&self, area: Rect, buf: &mut Buffer, state: &mut ReleaseViewState) -> Rect {
+        use tui::widgets::Widget;
+
+        state.art = None;
+
+        let art = match self.art {
+            Some(art) => art,
+            None => return area,
+        };
+
+        let width = area.width.min(Self::ART_WIDTH);
+        let height = (width / 2).min(area.height);
+        let draw = Rect {
+            width,
+            height,
+            ..area
+        };
+
+        Image::new(art).render(draw, buf);
+        state.art = Some(draw);
+
+        area.shrink_top(height + 1)
+    }
+
     fn date_duration(&self) -> String {
         use std::ops::Div;
 
@@ -78,11 +113,21 @@ impl<'a> ReleaseView<'a> {
     }
 
     fn draw_track_list(&self, area: Rect, buf: &mut Buffer, state: &mut ReleaseViewState) -> Rect {
+        let compilation = self.release.is_compilation();
+
         let mut track_list = self
             .release
             .tracks
             .iter()
-            .map(|track| track_text(track, area.width - 2, self.style))
+            .map(|track| {
+                // tracks that can't be streamed can't be played either
+                let style = match track.streamable() {
+                    Some(_) => self.style,
+                    None => self.style.dim(),
+                };
+
+                track_text(track, compilation, area.width - 2, style)
+            })
             .map(ListItem::new)
             .collect::<Vec<_>>();
 
@@ -130,18 +175,35 @@ impl<'a> ReleaseView<'a> {
     }
 }
 
-fn track_text(track: &Track, width: u16, style: Style) -> Spans<'_> {
+/// Formats a track, prefixing its title with its artist on compilations.
+fn track_text(track: &Track, compilation: bool, width: u16, style: Style) -> Spans<'_> {
+    let title: Cow<str> = match &track.artist {
+        Some(artist) if compilation => format!("{} - {}", artist, track.title).into(),
+        _ => track.title.as_str().into(),
+    };
+
+    timed_text(title, track.duration, width, style)
+}
+
+/// Formats a title with a duration, aligning the latter to the right and
+/// trimming the title if it doesn't fit into `width` columns.
+pub(crate) fn timed_text<'a>(
+    title: impl Into<Cow<'a, str>>,
+    duration: Duration,
+    width: u16,
+    style: Style,
+) -> Spans<'a> {
     const SPACE: &str = "   ";
     const SPACES: usize = SPACE.len();
 
-    let time = tracks::Time::from(track.duration).to_string();
+    let time = tracks::Time::from(duration).to_string();
     let time = Span::styled(time, style);
 
     let rem = usize::from(width)
         .saturating_sub(time.width())
         .saturating_sub(SPACES);
 
-    let mut title = Span::styled(&track.title, style);
+    let mut title = Span::styled(title, style);
     let title_width = title.width();
 
     let space = if title_width > rem {
@@ -188,6 +250,7 @@ fn newline() -> Spans<'static> {
 pub struct ReleaseViewState {
     track_list: ListState,
     playing: Option<usize>,
+    art: Option<Rect>,
 }
 
 impl std::ops::Deref for ReleaseViewState {
@@ -213,6 +276,11 @@ impl ReleaseViewState {
         self.playing
     }
 
+    /// Returns where the cover art was drawn last, if it was.
+    pub fn art_area(&self) -> Option<Rect> {
+        self.art
+    }
+
     pub fn selection_down(&mut self) {
         let new = self.selected().map(|sel| sel + 1).unwrap_or_default();
 
@@ -250,6 +318,7 @@ impl<'a> StatefulWidget for ReleaseView<'a> {
     type State = ReleaseViewState;
 
     fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let area = self.draw_art(area, buf, state);
         let area = self.draw_heading(area, buf);
         let area = self.draw_track_list(area.shrink_top(1), buf, state);
         self.draw_rest(area.shrink_top(1), buf);
@@ -263,11 +332,17 @@ mod test {
     #[test]
     fn track_text() {
         let mut track = Track {
+            id: None,
+            track_num: Some(1),
             title: "short".into(),
-            stream: Stream {
+            artist: None,
+            stream: Some(Stream {
                 mp3_128: "a://b.c".parse().unwrap(),
-            },
+            }),
             duration: Duration::from_secs(66),
+            is_streamable: true,
+            has_lyrics: false,
+            lyrics: None,
         };
 
         assert_eq!("short                       1:06", formatted(&track));
@@ -283,13 +358,22 @@ mod test {
         track.title = "just one char too long! :(".into();
 
         assert_eq!("just one char too long! …   1:06", formatted(&track));
+
+        track.title = "short".into();
+        track.artist = Some("someone".into());
+
+        assert_eq!("short                       1:06", formatted(&track));
+        assert_eq!(
+            "someone - short             1:06",
+            spans_text(super::track_text(&track, true, 32, <_>::default()))
+        );
     }
 
     fn formatted(track: &Track) -> String {
-        super::track_text(&track, 32, <_>::default())
-            .0
-            .iter()
-            .map(|span| span.content.as_ref())
-            .collect()
+        spans_text(super::track_text(track, false, 32, <_>::default()))
+    }
+
+    fn spans_text(spans: Spans) -> String {
+        spans.0.iter().map(|span| span.content.as_ref()).collect()
     }
 }
@@ -16,6 +16,7 @@ use {
 
 pub struct ResultList<'a> {
     results: &'a [SearchResult],
+    more: bool,
     style: Style,
     highlight_style: Style,
 }
@@ -24,12 +25,15 @@ impl<'a> ResultList<'a> {
     pub fn new(results: &'a [SearchResult]) -> ResultList<'a> {
         ResultList {
             results,
+            more: false,
             style: <_>::default(),
             highlight_style: <_>::default(),
         }
     }
 
     builder_methods! {
+        /// Whether to show a row for loading more results at the end.
+        pub more: bool;
         pub style: Style;
         pub highlight_style: Style
     }
@@ -38,6 +42,13 @@ impl<'a> ResultList<'a> {
 #[derive(Default)]
 pub struct ResultListState {
     list: ListState,
+    loading: bool,
+}
+
+impl ResultListState {
+    pub fn set_loading(&mut self, loading: bool) {
+        self.loading = loading
+    }
 }
 
 impl std::ops::Deref for ResultListState {
@@ -58,7 +69,18 @@ impl<'a> StatefulWidget for ResultList<'a> {
     type State = ResultListState;
 
     fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
-        let items = self.results.iter().map(list_item).collect::<Vec<_>>();
+        let last = if state.loading {
+            "Loading more..."
+        } else {
+            "Load more..."
+        };
+
+        let items = self
+            .results
+            .iter()
+            .map(list_item)
+            .chain(self.more.then(|| ListItem::new(last)))
+            .collect::<Vec<_>>();
 
         List::new(items)
             .style(self.style)
@@ -3,3 +3,4 @@ pub const ALBUM: char = '📀';
 pub const LABEL: char = '📻';
 pub const ARTIST: char = '🎤';
 pub const FAN: char = '🎧';
+pub const DISCOVER: char = '🧭';
@@ -5,7 +5,7 @@ use {
     gen_tui::{
         layout::RectExt,
         style::StyleExt,
-        widgets::{progress, ProgressBar, ScrollToFit, ScrollToFitState},
+        widgets::{progress, Image, Pixels, ProgressBar, ScrollToFit, ScrollToFitState},
     },
     std::time::Duration,
     tui::{buffer::Buffer, layout::Rect, style::Style, text::Span, widgets::StatefulWidget},
@@ -14,31 +14,72 @@ use {
 pub struct PlayBar<'a> {
     artist: &'a str,
     track: &'a Track,
+    art: Option<&'a Pixels>,
     elapsed: Time,
     volume: f32,
+    shuffle: bool,
+    repeat: Repeat,
     style: Style,
     bar_style: Style,
 }
 
+/// Which tracks are played again once they're over.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum Repeat {
+    #[default]
+    Off,
+    One,
+    All,
+}
+
 impl<'a> PlayBar<'a> {
     pub fn new(artist: &'a str, track: &'a Track) -> PlayBar<'a> {
         PlayBar {
             artist,
             track,
+            art: None,
             elapsed: <_>::default(),
             volume: 1.,
+            shuffle: false,
+            repeat: <_>::default(),
             style: <_>::default(),
             bar_style: <_>::default(),
         }
     }
 
     builder_methods! {
+        pub art: impl Into<Option<&'a Pixels>> => art.into();
         pub elapsed: impl Into<Time> => elapsed.into();
         pub bar_style: Style;
         pub volume: f32;
+        pub shuffle: bool;
+        pub repeat: Repeat;
         pub style: Style
     }
 
+    /// Draws a thumbnail of the cover art on the left, as
+    /// wide as it is high.
+    fn draw_art(&self, area: Rect, buf: &mut Buffer, state: &mut PlayBarState) -> Rect {
+        use tui::widgets::Widget;
+
+        state.art = None;
+
+        let art = match self.art {
+            Some(art) => art,
+            None => return area,
+        };
+
+        let draw = Rect {
+            width: (area.height * 2).min(area.width),
+            ..area
+        };
+
+        Image::new(art).render(draw, buf);
+        state.art = Some(draw);
+
+        area.shrink_left(draw.width + 1)
+    }
+
     fn draw_track_info(&self, area: Rect, buf: &mut Buffer, state: &mut PlayBarState) -> Rect {
         let width = area.width / 5;
 
@@ -77,6 +118,26 @@ impl<'a> PlayBar<'a> {
         area.shrink_right(WIDTH)
     }
 
+    fn draw_mode(&self, area: Rect, buf: &mut Buffer) -> Rect {
+        const WIDTH: u16 = 6;
+
+        let repeat = match self.repeat {
+            Repeat::Off => ' ',
+            Repeat::One => '🔂',
+            Repeat::All => '🔁',
+        };
+
+        let shuffle = if self.shuffle { '🔀' } else { ' ' };
+
+        let text = format!("{} {}", shuffle, repeat);
+
+        let Rect { x, y, .. } = area.scale_from_right(WIDTH).shrink_top(1);
+
+        buf.set_span(x, y, &Span::styled(text, self.style), WIDTH);
+
+        area.shrink_right(WIDTH)
+    }
+
     fn draw_bar(&self, area: Rect, buf: &mut Buffer) {
         use tui::widgets::Widget;
 
@@ -109,14 +170,24 @@ fn speaker(volume: f32) -> char {
 pub struct PlayBarState {
     artist: ScrollToFitState,
     title: ScrollToFitState,
+    art: Option<Rect>,
+}
+
+impl PlayBarState {
+    /// Returns where the thumbnail was drawn last, if it was.
+    pub fn art_area(&self) -> Option<Rect> {
+        self.art
+    }
 }
 
 impl<'a> StatefulWidget for PlayBar<'a> {
     type State = PlayBarState;
 
     fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let area = self.draw_art(area, buf, state);
         let area = self.draw_track_info(area, buf, state);
         let area = self.draw_volume(area, buf);
+        let area = self.draw_mode(area, buf);
         self.draw_bar(area, buf);
     }
 }
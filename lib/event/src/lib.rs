@@ -5,8 +5,9 @@ use {
 
 pub enum Event {
     Input(input::Key),
-    Response(fetch::Response),
+    Response(Box<fetch::Response>),
     MediaKey(media_keys::MediaKey),
+    Remote(remote::Call),
     DeviceUpdated,
     Terminate,
 }
@@ -25,16 +26,21 @@ pub struct Events {
     responses: mpsc::Receiver<fetch::Response>,
     device_watcher: device::Watcher,
     media_key_listener: media_keys::Listener,
+    remote: Option<remote::Server>,
 }
 
 impl Events {
-    pub fn new(responses: mpsc::Receiver<fetch::Response>) -> Result<Events, Error> {
+    pub fn new(
+        responses: mpsc::Receiver<fetch::Response>,
+        remote: Option<remote::Server>,
+    ) -> Result<Events, Error> {
         terminate::install().context(Terminate)?;
 
         Ok(Events {
             responses,
             device_watcher: device::Watcher::new().context(Device)?,
             media_key_listener: media_keys::Listener::new().context(MediaKeys)?,
+            remote,
         })
     }
 
@@ -43,18 +49,32 @@ impl Events {
             .map(Event::Input)
             .chain(self.responses())
             .chain(self.media_keys())
+            .chain(self.remote_calls())
             .chain(self.device_update())
             .chain(self.should_terminate())
     }
 
+    /// Lets the system know what's currently playing,
+    /// where that's supported.
+    pub fn publish(&self, playback: &media_keys::Playback) {
+        self.media_key_listener.publish(playback)
+    }
+
     fn responses(&self) -> impl Iterator<Item = Event> + '_ {
-        self.responses.try_iter().map(Event::Response)
+        self.responses.try_iter().map(Box::new).map(Event::Response)
     }
 
     fn media_keys(&self) -> impl Iterator<Item = Event> + '_ {
         self.media_key_listener.keys().map(Event::MediaKey)
     }
 
+    fn remote_calls(&self) -> impl Iterator<Item = Event> + '_ {
+        self.remote
+            .iter()
+            .flat_map(remote::Server::calls)
+            .map(Event::Remote)
+    }
+
     fn device_update(&self) -> impl Iterator<Item = Event> {
         event_if(self.device_watcher.device_updated(), Event::DeviceUpdated)
     }
@@ -0,0 +1,72 @@
+use {
+    bandcamp_api::data::common::{Art, ArtSize, Id},
+    image::{imageops::FilterType, RgbImage},
+    reqwest::blocking::Client,
+    snafu::{ResultExt, Snafu},
+};
+
+#[derive(Debug, Snafu)]
+pub enum ArtError {
+    #[snafu(display("error fetching cover art {}: {}", id, source))]
+    Request { source: reqwest::Error, id: Id<Art> },
+    #[snafu(display("error decoding cover art {}: {}", id, source))]
+    Decode {
+        source: image::ImageError,
+        id: Id<Art>,
+    },
+}
+
+/// Cover art, decoded and scaled to the sizes it's drawn in.
+#[derive(Debug, Clone)]
+pub struct Cover {
+    /// A small version for drawing with text, where every pixel takes up
+    /// half a cell.
+    pub small: Picture,
+    /// The whole image, for terminals that can draw actual images.
+    pub full: Picture,
+}
+
+/// RGB pixels, row by row.
+#[derive(Debug, Clone)]
+pub struct Picture {
+    pub width: u32,
+    pub height: u32,
+    pub rgb: Vec<u8>,
+}
+
+impl From<RgbImage> for Picture {
+    fn from(image: RgbImage) -> Self {
+        Picture {
+            width: image.width(),
+            height: image.height(),
+            rgb: image.into_raw(),
+        }
+    }
+}
+
+impl Cover {
+    /// The width of the small version, which is enough to fill the
+    /// release pane of most terminals.
+    const SMALL: u32 = 64;
+
+    pub(crate) fn fetch(id: Id<Art>, client: &Client) -> Result<Cover, ArtError> {
+        let bytes = client
+            .get(id.url(ArtSize::Medium))
+            .send()
+            .and_then(|response| response.error_for_status())
+            .and_then(|response| response.bytes())
+            .context(Request { id })?;
+
+        let full = image::load_from_memory(&bytes)
+            .context(Decode { id })?
+            .into_rgb8();
+
+        let height = (Cover::SMALL * full.height() / full.width().max(1)).max(1);
+        let small = image::imageops::resize(&full, Cover::SMALL, height, FilterType::Triangle);
+
+        Ok(Cover {
+            small: small.into(),
+            full: full.into(),
+        })
+    }
+}
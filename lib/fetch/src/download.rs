@@ -0,0 +1,269 @@
+use {
+    bandcamp_api::data::{
+        common::Date,
+        releases::{Release, Stream},
+    },
+    id3::{Tag, TagLike, Timestamp, Version},
+    reqwest::blocking::Client,
+    snafu::{ResultExt, Snafu},
+    std::{
+        fs::{self, File},
+        io::{self, Read, Write},
+        path::{Path, PathBuf},
+    },
+};
+
+#[derive(Debug, Snafu)]
+pub enum DownloadError {
+    #[snafu(display("error downloading {}: {}", title, source))]
+    Request {
+        source: reqwest::Error,
+        title: String,
+    },
+    #[snafu(display("error saving {}: {}", path.display(), source))]
+    Save { source: io::Error, path: PathBuf },
+    #[snafu(display("error tagging {}: {}", path.display(), source))]
+    Tagging { source: id3::Error, path: PathBuf },
+}
+
+pub type Result<T = (), E = DownloadError> = std::result::Result<T, E>;
+
+/// A track to save to disk, along with the tags to write to it.
+#[derive(Debug, Clone)]
+pub struct Download {
+    pub stream: Stream,
+    pub path: PathBuf,
+    pub tags: Tags,
+}
+
+#[derive(Debug, Clone)]
+pub struct Tags {
+    pub title: String,
+    pub artist: String,
+    pub album: String,
+    pub track: u32,
+    pub total_tracks: u32,
+    pub date: Date,
+}
+
+impl Download {
+    /// Creates downloads for the tracks at the provided positions in a release,
+    /// placing them in a directory for the release inside `dir`.
+    /// Tracks that can't be streamed can't be downloaded either.
+    pub fn from_release(
+        release: &Release,
+        tracks: impl IntoIterator<Item = usize>,
+        dir: &Path,
+    ) -> Vec<Download> {
+        let info = &release.info;
+        let dir = dir.join(sanitize(&info.artist)).join(sanitize(&info.title));
+
+        tracks
+            .into_iter()
+            .filter_map(|idx| {
+                let track = release.tracks.get(idx)?;
+                Some((idx, track, track.streamable()?))
+            })
+            .map(|(idx, track, stream)| {
+                let number = track.track_num.unwrap_or(idx as u32 + 1);
+
+                Download {
+                    stream: stream.clone(),
+                    path: dir.join(format!("{:02} {}.mp3", number, sanitize(&track.title))),
+                    tags: Tags {
+                        title: track.title.clone(),
+                        artist: track.artist.clone().unwrap_or_else(|| info.artist.clone()),
+                        album: info.title.clone(),
+                        track: number,
+                        total_tracks: release.tracks.len() as u32,
+                        date: info.release_date,
+                    },
+                }
+            })
+            .collect()
+    }
+
+    /// Saves the track to its path, calling `progress` with the
+    /// number of bytes written so far and the total, if known.
+    pub fn save(&self, client: &Client, mut progress: impl FnMut(u64, Option<u64>)) -> Result {
+        const CHUNK: usize = 64 * 1024;
+
+        let mut response = client
+            .get(self.stream.mp3_128.clone())
+            .send()
+            .and_then(|response| response.error_for_status())
+            .context(Request {
+                title: &self.tags.title,
+            })?;
+
+        let total = response.content_length();
+
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).context(Save { path: dir })?
+        }
+
+        // the file is only given its proper name once it's complete,
+        // so aborted downloads don't look like finished ones
+        let partial = self.path.with_extension("mp3.part");
+        let mut file = File::create(&partial).context(Save { path: &partial })?;
+
+        let mut buf = vec![0; CHUNK];
+        let mut written = 0;
+
+        loop {
+            let read = response.read(&mut buf).context(Save { path: &partial })?;
+
+            if read == 0 {
+                break
+            }
+
+            file.write_all(&buf[..read])
+                .context(Save { path: &partial })?;
+
+            written += read as u64;
+            progress(written, total)
+        }
+
+        drop(file);
+
+        self.tags
+            .to_id3()
+            .write_to_path(&partial, Version::Id3v24)
+            .context(Tagging { path: &partial })?;
+
+        fs::rename(&partial, &self.path).context(Save { path: &self.path })
+    }
+}
+
+impl Tags {
+    fn to_id3(&self) -> Tag {
+        let mut tag = Tag::new();
+
+        tag.set_title(&self.title);
+        tag.set_artist(&self.artist);
+        tag.set_album(&self.album);
+        tag.set_track(self.track);
+        tag.set_total_tracks(self.total_tracks);
+
+        let date = Timestamp {
+            year: self.date.year.into(),
+            month: Some(self.date.month as u8),
+            day: Some(self.date.day.get()),
+            hour: None,
+            minute: None,
+            second: None,
+        };
+
+        tag.set_date_released(date);
+        tag.set_date_recorded(date);
+
+        tag
+    }
+}
+
+/// Replaces characters that aren't allowed in file names on common platforms.
+/// Names that are empty or only dots would refer to the wrong directory,
+/// so they're replaced entirely.
+fn sanitize(name: &str) -> String {
+    let name: String = name
+        .trim()
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+
+    if name.chars().all(|c| c == '.') {
+        "_".into()
+    } else {
+        name
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::*,
+        bandcamp_api::data::{
+            common::{Id, Month},
+            releases::{Band, Info, ReleaseKind, Track},
+        },
+        std::{num::NonZeroU8, time::Duration},
+    };
+
+    #[test]
+    fn from_release() {
+        let track = |title: &str| Track {
+            id: None,
+            track_num: None,
+            title: title.into(),
+            artist: None,
+            duration: Duration::from_secs(1),
+            stream: Some(Stream {
+                mp3_128: "https://example.com".parse().unwrap(),
+            }),
+            is_streamable: true,
+            has_lyrics: false,
+            lyrics: None,
+        };
+
+        let mut release = Release {
+            info: Info {
+                id: Id::new(1),
+                kind: ReleaseKind::Album,
+                band: Band {
+                    id: Id::new(2),
+                    name: "AC/DC".into(),
+                    location: None,
+                },
+                artist: "AC/DC".into(),
+                title: "What?".into(),
+                art_id: None,
+                about: None,
+                credits: None,
+                release_date: Date {
+                    day: NonZeroU8::new(1).unwrap(),
+                    month: Month::March,
+                    year: 2020,
+                },
+                tags: vec![],
+                label: None,
+                label_id: None,
+            },
+            tracks: vec![track("one"), track("two"), track("three")],
+        };
+
+        let downloads = Download::from_release(&release, vec![1, 5], "dl".as_ref());
+
+        assert_eq!(downloads.len(), 1);
+        assert_eq!(
+            downloads[0].path,
+            Path::new("dl")
+                .join("AC_DC")
+                .join("What_")
+                .join("02 two.mp3")
+        );
+        assert_eq!(downloads[0].tags.track, 2);
+        assert_eq!(downloads[0].tags.total_tracks, 3);
+
+        release.info.artist = "..".into();
+        release.info.title = " . ".into();
+
+        let downloads = Download::from_release(&release, vec![0], "dl".as_ref());
+
+        assert_eq!(
+            downloads[0].path,
+            Path::new("dl").join("_").join("_").join("01 one.mp3")
+        );
+
+        // the number the release gives a track takes precedence over its position
+        release.tracks[2].track_num = Some(7);
+
+        let downloads = Download::from_release(&release, vec![2], "dl".as_ref());
+
+        assert_eq!(downloads[0].path.file_name().unwrap(), "07 three.mp3");
+        assert_eq!(downloads[0].tags.track, 7);
+    }
+}
@@ -1,22 +1,104 @@
+pub mod art;
+pub mod download;
 mod pool;
 
 use {
+    art::{ArtError, Cover},
     bandcamp_api::{
-        data::{fans::Fan, outlets::Outlet, releases::Release, search::Search, Query},
+        data::{
+            common::{Art, Id},
+            discover::{Discover, DiscoverArgs},
+            fans::Fan,
+            outlets::Outlet,
+            releases::{Lyrics, Release, ReleaseArgs, Stream},
+            search::{Search, SearchArgs},
+            Query,
+        },
+        resolve::{self, Resolved},
         Api,
+        Cached,
         Result,
     },
-    bc_track::TrackStream,
+    bc_track::{Reconnect, Recovery, Refresh, SegmentCache, Source},
+    download::Download,
     pool::ThreadPool,
-    std::sync::mpsc,
+    std::{
+        cell::Cell,
+        io::{self, Seek},
+        path::PathBuf,
+        sync::mpsc,
+        time::Duration,
+    },
 };
 
 pub enum Response {
     Fan(Result<Fan>),
     Search(Result<Search>),
+    Discover(Result<Discover>),
     Outlet(Result<Outlet>),
     Release(Result<Release>),
-    Track(Result<Box<bc_track::TrackStream>>),
+    Lyrics(Result<Lyrics>),
+    Track(io::Result<Opened>),
+    Resolved(resolve::Result<Resolved>),
+    /// A release has been fetched again because the URL
+    /// of one of its tracks expired while it was streamed.
+    Refreshed(Release),
+    /// The up to date response to a query whose
+    /// expired response was sent from the cache before.
+    Updated(Box<Response>),
+    Download(DownloadStatus),
+    Art(Id<Art>, std::result::Result<Cover, ArtError>),
+}
+
+/// A track that's been fetched or opened and is ready to be played.
+pub struct Opened {
+    pub source: Box<Source>,
+    /// Whether the track begins with silence, rather than
+    /// carrying on from the one before it without a gap.
+    pub starts_silent: bool,
+}
+
+impl Opened {
+    /// How long a track has to start with silence
+    /// for there to be a gap before it.
+    const LEAD_IN: Duration = Duration::from_millis(50);
+
+    /// Decodes the start of the track to find out whether it's silent,
+    /// which is done here so that the I/O doesn't block the caller.
+    fn new(source: io::Result<Source>) -> io::Result<Opened> {
+        let mut source = source?;
+        let starts_silent = mp3::Mp3::new(&mut source).starts_silent(Opened::LEAD_IN);
+        source.seek(io::SeekFrom::Start(0))?;
+
+        Ok(Opened {
+            source: Box::new(source),
+            starts_silent,
+        })
+    }
+}
+
+/// How far along a batch of downloads is.
+#[derive(Debug, Clone)]
+pub struct Progress {
+    pub batch: usize,
+    /// The title of the track being downloaded.
+    pub title: String,
+    /// The position of the track in the batch.
+    pub track: usize,
+    pub tracks: usize,
+    /// The fraction of the track that has been downloaded, if its size is
+    /// known.
+    pub fraction: Option<f32>,
+}
+
+pub enum DownloadStatus {
+    Progress(Progress),
+    /// A track of the batch has been saved successfully.
+    Saved(Download),
+    Finished {
+        batch: usize,
+        result: download::Result,
+    },
 }
 
 macro_rules! from {
@@ -33,11 +115,15 @@ from!(Fan, Result<Fan>);
 from!(Search, Result<Search>);
 from!(Outlet, Result<Outlet>);
 from!(Release, Result<Release>);
+from!(Lyrics, Result<Lyrics>);
 
 pub struct Fetcher {
     api: Api,
     pool: ThreadPool,
     sender: mpsc::Sender<Response>,
+    batches: Cell<usize>,
+    segments: Option<SegmentCache>,
+    reconnect: Reconnect,
 }
 
 impl Fetcher {
@@ -48,11 +134,24 @@ impl Fetcher {
             api,
             pool: ThreadPool::new(),
             sender,
+            batches: <_>::default(),
+            segments: None,
+            reconnect: <_>::default(),
         };
 
         (fetcher, receiver)
     }
 
+    /// Stores the audio of streamed tracks in the provided cache.
+    pub fn cache_segments(&mut self, cache: SegmentCache) {
+        self.segments = Some(cache)
+    }
+
+    /// Sets how streamed tracks reconnect after their connection drops.
+    pub fn reconnect(&mut self, reconnect: Reconnect) {
+        self.reconnect = reconnect
+    }
+
     pub fn query<T, A>(&self, args: &A)
     where
         T: Query<A> + Send + 'static,
@@ -64,19 +163,165 @@ impl Fetcher {
 
         let req = api.request(args);
 
+        self.pool.spawn(move || match api.cached(&req) {
+            Some(Cached {
+                value: Ok(value),
+                expired,
+            }) => {
+                let _ = sender.send(Ok(value).into());
+
+                // the cached response is shown right away,
+                // but it's replaced once the current one arrives
+                if expired {
+                    if let Ok(value) = api.execute(req) {
+                        let _ = sender.send(Response::Updated(Box::new(Ok(value).into())));
+                    }
+                }
+            }
+            // responses that can't be parsed anymore are fetched again
+            _ => {
+                let _ = sender.send(api.execute(req).into());
+            }
+        })
+    }
+
+    /// Fetches a page of discovered releases, remembering the
+    /// arguments so the next page can be fetched later.
+    pub fn discover(&self, args: DiscoverArgs) {
+        let api = self.api.clone();
+        let sender = self.sender.clone();
+
         self.pool.spawn(move || {
-            let response = api.execute(req).into();
-            let _ = sender.send(response);
+            let discover = api.query::<Discover, _>(&args).map(|discover| Discover {
+                args: Some(args),
+                ..discover
+            });
+
+            let _ = sender.send(Response::Discover(discover));
         })
     }
 
-    pub fn fetch_track(&self, url: reqwest::Url) {
+    /// Fetches a page of the results for a full search.
+    pub fn search(&self, args: SearchArgs) {
+        let api = self.api.clone();
+        let sender = self.sender.clone();
+
+        self.pool.spawn(move || {
+            let _ = sender.send(Response::Search(api.search(&args)));
+        })
+    }
+
+    /// Fetches the cover art with the provided id, decoding
+    /// and scaling it before it's sent along with the responses.
+    pub fn art(&self, id: Id<Art>) {
+        if self.api.is_offline() {
+            return
+        }
+
         let client = self.api.client().clone();
         let sender = self.sender.clone();
 
         self.pool.spawn(move || {
-            let stream = TrackStream::new(url, client);
-            let _ = sender.send(Response::Track(stream.map(Box::new)));
+            let _ = sender.send(Response::Art(id, Cover::fetch(id, &client)));
+        })
+    }
+
+    pub fn resolve(&self, url: reqwest::Url) {
+        let api = self.api.clone();
+        let sender = self.sender.clone();
+
+        self.pool.spawn(move || {
+            let _ = sender.send(Response::Resolved(api.resolve(&url)));
+        })
+    }
+
+    /// Starts saving the provided tracks to disk one after the other,
+    /// sending their progress along with the responses.
+    /// Returns the number identifying the batch.
+    pub fn download(&self, downloads: Vec<Download>) -> usize {
+        let batch = self.batches.replace(self.batches.get() + 1);
+        let client = self.api.client().clone();
+        let sender = self.sender.clone();
+
+        self.pool.spawn(move || {
+            let send = |status| {
+                let _ = sender.send(Response::Download(status));
+            };
+
+            let tracks = downloads.len();
+
+            let result = downloads
+                .into_iter()
+                .enumerate()
+                .try_for_each(|(track, download)| {
+                    download.save(&client, |written, total| {
+                        send(DownloadStatus::Progress(Progress {
+                            batch,
+                            title: download.tags.title.clone(),
+                            track,
+                            tracks,
+                            fraction: total.map(|total| written as f32 / total as f32),
+                        }))
+                    })?;
+
+                    send(DownloadStatus::Saved(download));
+                    Ok(())
+                });
+
+            send(DownloadStatus::Finished { batch, result });
+        });
+
+        batch
+    }
+
+    /// Starts streaming a track of the release with the provided arguments.
+    /// If the track's URL expires, the release is fetched again to get a new
+    /// one, which is also sent along with the responses.
+    pub fn fetch_track(&self, stream: &Stream, release: ReleaseArgs) {
+        let url = stream.mp3_128.clone();
+        let client = self.api.client().clone();
+        let sender = self.sender.clone();
+        let segments = self.segments.clone();
+        let recovery = Recovery {
+            reconnect: self.reconnect,
+            refresh: stream.track_id().map(|id| self.refresh(id, release)),
+        };
+
+        self.pool.spawn(move || {
+            let source = Source::fetch(url, client, recovery, segments.as_ref());
+            let _ = sender.send(Response::Track(Opened::new(source)));
+        })
+    }
+
+    fn refresh(&self, track: u64, release: ReleaseArgs) -> Refresh {
+        let api = self.api.clone();
+        let sender = self.sender.clone();
+
+        Box::new(move || {
+            // the cached release would contain the expired URL
+            let release = api.execute(api.request::<Release, _>(&release)).ok()?;
+            let url = release
+                .tracks
+                .iter()
+                .filter_map(|t| t.streamable())
+                .find(|stream| stream.track_id() == Some(track))?
+                .mp3_128
+                .clone();
+
+            let _ = sender.send(Response::Refreshed(release));
+
+            Some(url)
+        })
+    }
+
+    /// Opens a local copy of the track at the provided URL,
+    /// sending it along with the responses like a fetched one.
+    pub fn open_track(&self, url: reqwest::Url, path: PathBuf) {
+        let sender = self.sender.clone();
+
+        self.pool.spawn(move || {
+            let source = Source::open(url, path);
+            let _ = sender.send(Response::Track(Opened::new(source)));
         })
     }
 }
@@ -0,0 +1,347 @@
+use {
+    super::{sixel, Pixels},
+    base64::{engine::general_purpose::STANDARD, Engine},
+    std::{
+        collections::{HashMap, HashSet},
+        env,
+        io::{self, Write},
+        mem,
+        sync::{mpsc, Arc},
+        thread,
+    },
+    tui::{
+        backend::Backend,
+        buffer::{Buffer, Cell},
+        layout::Rect,
+        widgets::Widget,
+    },
+};
+
+/// The ways images can be shown in a terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Protocol {
+    /// Unicode half blocks in true colour, which work almost anywhere.
+    HalfBlocks,
+    /// The graphics protocol of kitty, which other terminals have adopted.
+    Kitty,
+    Sixel,
+}
+
+impl Protocol {
+    /// Guesses the protocol with the highest resolution
+    /// that the terminal supports from the environment.
+    pub fn detect() -> Protocol {
+        let var = |name| env::var(name).unwrap_or_default();
+
+        let term = var("TERM");
+        let program = var("TERM_PROGRAM");
+
+        if env::var_os("KITTY_WINDOW_ID").is_some()
+            || term.contains("kitty")
+            || matches!(program.as_str(), "WezTerm" | "ghostty")
+        {
+            Protocol::Kitty
+        } else if ["foot", "mlterm", "yaft", "contour"]
+            .iter()
+            .any(|sixel| term.starts_with(sixel))
+            || term.contains("sixel")
+        {
+            Protocol::Sixel
+        } else {
+            Protocol::HalfBlocks
+        }
+    }
+}
+
+/// Shows images in full resolution on top of the cells drawn by tui, using
+/// one of the terminal's graphics protocols. Images are placed while a frame
+/// is drawn and shown by [`flush`](Graphics::flush) once it's done.
+///
+/// Rendering the graphics as a widget after everything else keeps the cells
+/// below sixel images, so they can be drawn again to remove the images.
+pub struct Graphics {
+    protocol: Protocol,
+    /// The size of a cell in pixels, which sixel images are scaled to.
+    cell: (u16, u16),
+    placed: Vec<Placement>,
+    shown: Vec<Placement>,
+    /// What tui drew below the sixel images that are shown.
+    covered: Vec<(u16, u16, Cell)>,
+    /// The ids the terminal knows transmitted images by, for
+    /// protocols that keep them around.
+    transmitted: HashMap<u64, u32>,
+    next_transmitted: u32,
+    /// Transmitted images that aren't needed anymore.
+    forgotten: Vec<u32>,
+    encoder: Encoder,
+}
+
+#[derive(Clone)]
+struct Placement {
+    id: u64,
+    area: Rect,
+    pixels: Arc<Pixels>,
+}
+
+impl PartialEq for Placement {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id && self.area == other.area
+    }
+}
+
+/// An image scaled to a size in pixels.
+type Scaled = (u64, u32, u32);
+
+/// Scales and encodes sixel images in the background,
+/// keeping them for as long as they may be shown.
+struct Encoder {
+    encoded: HashMap<Scaled, String>,
+    pending: HashSet<Scaled>,
+    sender: mpsc::Sender<(Scaled, String)>,
+    receiver: mpsc::Receiver<(Scaled, String)>,
+}
+
+impl Default for Encoder {
+    fn default() -> Self {
+        let (sender, receiver) = mpsc::channel();
+
+        Encoder {
+            encoded: <_>::default(),
+            pending: <_>::default(),
+            sender,
+            receiver,
+        }
+    }
+}
+
+impl Encoder {
+    /// Returns the encoded image, or starts encoding it if that hasn't
+    /// happened yet.
+    fn get(&mut self, key: Scaled, pixels: &Arc<Pixels>) -> Option<&str> {
+        for (key, sixels) in self.receiver.try_iter() {
+            if self.pending.remove(&key) {
+                self.encoded.insert(key, sixels);
+            }
+        }
+
+        if !self.encoded.contains_key(&key) && self.pending.insert(key) {
+            let (_, width, height) = key;
+            let pixels = pixels.clone();
+            let sender = self.sender.clone();
+
+            thread::spawn(move || {
+                let _ = sender.send((key, sixel::encode(&pixels.resize(width, height))));
+            });
+        }
+
+        self.encoded.get(&key).map(String::as_str)
+    }
+
+    fn forget(&mut self, id: u64) {
+        self.encoded.retain(|(image, ..), _| *image != id);
+        self.pending.retain(|(image, ..)| *image != id);
+    }
+}
+
+impl Graphics {
+    pub fn new(protocol: Protocol) -> Graphics {
+        Graphics {
+            protocol,
+            cell: (8, 16),
+            placed: vec![],
+            shown: vec![],
+            covered: vec![],
+            transmitted: <_>::default(),
+            next_transmitted: 1,
+            forgotten: vec![],
+            encoder: <_>::default(),
+        }
+    }
+
+    pub fn protocol(&self) -> Protocol {
+        self.protocol
+    }
+
+    /// Sets the size of a cell in pixels, if it's known.
+    pub fn cell_size(self, width: u16, height: u16) -> Self {
+        Graphics {
+            cell: (width, height),
+            ..self
+        }
+    }
+
+    /// Places the image with the provided id in the area for the next frame.
+    /// Images that aren't placed again aren't shown anymore.
+    pub fn place(&mut self, id: u64, pixels: &Arc<Pixels>, area: Rect) {
+        if self.protocol != Protocol::HalfBlocks && area.area() > 0 {
+            self.placed.push(Placement {
+                id,
+                area,
+                pixels: pixels.clone(),
+            })
+        }
+    }
+
+    /// Frees what's kept of the image with the provided id,
+    /// because it won't be placed again.
+    pub fn forget(&mut self, id: u64) {
+        self.encoder.forget(id);
+
+        if let Some(image) = self.transmitted.remove(&id) {
+            self.forgotten.push(image)
+        }
+    }
+
+    /// Shows the images that have been placed since the last call
+    /// within the provided screen, removing the ones that aren't
+    /// placed anymore.
+    pub fn flush<B: Backend + Write>(&mut self, backend: &mut B, screen: Rect) -> io::Result<()> {
+        let mut placed = mem::take(&mut self.placed);
+
+        if self.protocol == Protocol::Sixel {
+            let (width, height) = self.cell;
+            let encoder = &mut self.encoder;
+
+            // the terminal scrolls when a sixel image ends on its last line,
+            // and images are only shown once they've been encoded
+            placed.retain(|Placement { id, area, pixels }| {
+                let size = (
+                    u32::from(area.width * width),
+                    u32::from(area.height * height),
+                );
+
+                area.bottom() < screen.bottom()
+                    && encoder.get((*id, size.0, size.1), pixels).is_some()
+            })
+        }
+
+        if placed == self.shown && self.forgotten.is_empty() {
+            return Ok(())
+        }
+
+        // the cursor is where tui left it
+        write!(backend, "\x1b7")?;
+
+        match self.protocol {
+            Protocol::HalfBlocks => {}
+            Protocol::Kitty => self.show_kitty(backend, &placed)?,
+            Protocol::Sixel => self.show_sixel(backend, &placed)?,
+        }
+
+        write!(backend, "\x1b8")?;
+        Write::flush(backend)?;
+
+        self.shown = placed;
+
+        Ok(())
+    }
+
+    fn show_sixel<B: Backend + Write>(
+        &mut self,
+        backend: &mut B,
+        placed: &[Placement],
+    ) -> io::Result<()> {
+        // sixel images are part of the text, so they
+        // can only be removed by drawing over them
+        backend.draw(self.covered.iter().map(|(x, y, cell)| (*x, *y, cell)))?;
+
+        let (width, height) = self.cell;
+
+        for Placement { id, area, pixels } in placed {
+            let size = (
+                u32::from(area.width * width),
+                u32::from(area.height * height),
+            );
+
+            if let Some(sixels) = self.encoder.get((*id, size.0, size.1), pixels) {
+                move_to(backend, *area)?;
+                backend.write_all(sixels.as_bytes())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn show_kitty(&mut self, out: &mut impl Write, placed: &[Placement]) -> io::Result<()> {
+        const CHUNK: usize = 4096;
+
+        for image in self.forgotten.drain(..) {
+            // removes the image along with its placements
+            write!(out, "\x1b_Ga=d,d=I,i={},q=2\x1b\\", image)?;
+        }
+
+        // removes the placements, but keeps the images
+        write!(out, "\x1b_Ga=d,d=a,q=2\x1b\\")?;
+
+        for (idx, Placement { id, area, pixels }) in placed.iter().enumerate() {
+            let next = &mut self.next_transmitted;
+            let mut transmit = false;
+
+            let image = *self.transmitted.entry(*id).or_insert_with(|| {
+                transmit = true;
+                mem::replace(next, *next + 1)
+            });
+
+            if transmit {
+                let data = STANDARD.encode(pixels.rgb());
+                let mut chunks = data.as_bytes().chunks(CHUNK).peekable();
+
+                write!(
+                    out,
+                    "\x1b_Ga=t,f=24,s={},v={},i={},q=2,",
+                    pixels.width(),
+                    pixels.height(),
+                    image
+                )?;
+
+                while let Some(chunk) = chunks.next() {
+                    let more = chunks.peek().is_some() as u8;
+
+                    write!(out, "m={};", more)?;
+                    out.write_all(chunk)?;
+                    write!(out, "\x1b\\")?;
+
+                    if more == 1 {
+                        write!(out, "\x1b_G")?
+                    }
+                }
+            }
+
+            move_to(out, *area)?;
+            write!(
+                out,
+                "\x1b_Ga=p,i={},p={},c={},r={},C=1,q=2\x1b\\",
+                image,
+                idx + 1,
+                area.width,
+                area.height
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+fn move_to(out: &mut impl Write, area: Rect) -> io::Result<()> {
+    write!(out, "\x1b[{};{}H", area.y + 1, area.x + 1)
+}
+
+impl Widget for &mut Graphics {
+    fn render(self, _: Rect, buf: &mut Buffer) {
+        self.covered.clear();
+
+        if self.protocol != Protocol::Sixel {
+            return
+        }
+
+        for Placement { area, .. } in &self.shown {
+            let area = area.intersection(buf.area);
+
+            for y in area.top()..area.bottom() {
+                for x in area.left()..area.right() {
+                    self.covered.push((x, y, buf.get(x, y).clone()))
+                }
+            }
+        }
+    }
+}
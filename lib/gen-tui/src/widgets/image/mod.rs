@@ -0,0 +1,162 @@
+mod graphics;
+mod sixel;
+
+pub use graphics::{Graphics, Protocol};
+
+use {
+    std::ops::Range,
+    tui::{buffer::Buffer, layout::Rect, style::Color, widgets::Widget},
+};
+
+/// An image made up of rows of RGB pixels.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pixels {
+    width: u32,
+    height: u32,
+    rgb: Vec<u8>,
+}
+
+impl Pixels {
+    /// Returns `None` unless there are three bytes for each pixel.
+    pub fn new(width: u32, height: u32, rgb: Vec<u8>) -> Option<Pixels> {
+        (rgb.len() as u64 == u64::from(width) * u64::from(height) * 3).then_some(Pixels {
+            width,
+            height,
+            rgb,
+        })
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn rgb(&self) -> &[u8] {
+        &self.rgb
+    }
+
+    /// Scales the image to the provided size, averaging the
+    /// pixels that end up in the same one.
+    pub fn resize(&self, width: u32, height: u32) -> Pixels {
+        let mut rgb = Vec::with_capacity(width as usize * height as usize * 3);
+
+        for y in 0..height {
+            for x in 0..width {
+                let [r, g, b] =
+                    self.average(source(x, width, self.width), source(y, height, self.height));
+
+                rgb.extend_from_slice(&[r, g, b])
+            }
+        }
+
+        Pixels { width, height, rgb }
+    }
+
+    fn get(&self, x: u32, y: u32) -> [u8; 3] {
+        let idx = (y as usize * self.width as usize + x as usize) * 3;
+        [self.rgb[idx], self.rgb[idx + 1], self.rgb[idx + 2]]
+    }
+
+    fn average(&self, xs: Range<u32>, ys: Range<u32>) -> [u8; 3] {
+        let mut sum = [0u64; 3];
+        let mut count = 0;
+
+        for y in ys {
+            for x in xs.clone() {
+                let pixel = self.get(x, y);
+
+                for (sum, channel) in sum.iter_mut().zip(&pixel) {
+                    *sum += u64::from(*channel)
+                }
+
+                count += 1
+            }
+        }
+
+        sum.map(|sum| (sum / count.max(1)) as u8)
+    }
+}
+
+/// Returns the pixels of an image `from` pixels wide that end up in
+/// pixel `idx` when it's scaled to `to` pixels, which are at least one.
+fn source(idx: u32, to: u32, from: u32) -> Range<u32> {
+    let scale = |idx: u32| (u64::from(idx) * u64::from(from) / u64::from(to)) as u32;
+
+    let start = scale(idx).min(from.saturating_sub(1));
+    let end = scale(idx + 1).clamp(start + 1, from.max(1));
+
+    start..end
+}
+
+/// Draws an image with half block characters, each cell showing two pixels
+/// on top of each other in its foreground and background colour.
+/// The image is stretched to fill the whole area.
+pub struct Image<'a> {
+    pixels: &'a Pixels,
+}
+
+impl<'a> Image<'a> {
+    pub fn new(pixels: &'a Pixels) -> Self {
+        Image { pixels }
+    }
+}
+
+impl<'a> Widget for Image<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let pixels = self.pixels;
+
+        if pixels.width == 0 || pixels.height == 0 {
+            return
+        }
+
+        let width = u32::from(area.width);
+        let height = u32::from(area.height) * 2;
+
+        let color = |x, y| {
+            let [r, g, b] = pixels.average(
+                source(x, width, pixels.width),
+                source(y, height, pixels.height),
+            );
+
+            Color::Rgb(r, g, b)
+        };
+
+        for y in 0..area.height {
+            for x in 0..area.width {
+                let (px, py) = (u32::from(x), u32::from(y) * 2);
+
+                buf.get_mut(area.x + x, area.y + y)
+                    .set_char('▀')
+                    .set_fg(color(px, py))
+                    .set_bg(color(px, py + 1));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn resize() {
+        #[rustfmt::skip]
+        let pixels = Pixels::new(4, 2, vec![
+            0, 0, 0,  20, 20, 20,  100, 0, 0,  100, 0, 0,
+            40, 40, 40,  60, 60, 60,  0, 0, 100,  0, 0, 100,
+        ])
+        .unwrap();
+
+        let half = pixels.resize(2, 1);
+        assert_eq!(half.rgb(), &[30, 30, 30, 50, 0, 50]);
+
+        let double = half.resize(4, 2);
+        assert_eq!(double.get(1, 1), [30, 30, 30]);
+        assert_eq!(double.get(3, 0), [50, 0, 50]);
+
+        assert!(Pixels::new(1, 1, vec![0, 0]).is_none());
+    }
+}
@@ -0,0 +1,120 @@
+use {super::Pixels, std::fmt::Write};
+
+/// The number of levels of each channel in the palette.
+const LEVELS: u16 = 6;
+
+/// Encodes the image as sixels, reducing its colours to a palette that
+/// evenly covers all of them.
+pub(super) fn encode(pixels: &Pixels) -> String {
+    let (width, height) = (pixels.width(), pixels.height());
+
+    let colors = pixels
+        .rgb()
+        .chunks_exact(3)
+        .map(|rgb| {
+            rgb.iter().fold(0, |color, channel| {
+                color * LEVELS + (u16::from(*channel) * (LEVELS - 1) + 127) / 255
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let mut used = vec![false; usize::from(LEVELS.pow(3))];
+    colors
+        .iter()
+        .for_each(|color| used[usize::from(*color)] = true);
+
+    let used = used
+        .iter()
+        .enumerate()
+        .filter(|(_, used)| **used)
+        .map(|(color, _)| color)
+        .collect::<Vec<_>>();
+
+    let mut out = String::new();
+
+    let _ = write!(out, "\x1bPq\"1;1;{};{}", width, height);
+
+    for color in &used {
+        let level = |digit: u32| (*color as u16 / LEVELS.pow(digit) % LEVELS) * 100 / (LEVELS - 1);
+        let _ = write!(out, "#{};2;{};{};{}", color, level(2), level(1), level(0));
+    }
+
+    for band in (0..height).step_by(6) {
+        let rows = band..(band + 6).min(height);
+        let mut first = true;
+
+        for color in &used {
+            let sixels = (0..width)
+                .map(|x| {
+                    rows.clone().fold(0, |sixel, y| {
+                        let idx = (y * width + x) as usize;
+                        let set = usize::from(colors[idx]) == *color;
+
+                        sixel | (set as u8) << (y - band)
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            if sixels.iter().all(|sixel| *sixel == 0) {
+                continue
+            }
+
+            if !first {
+                // back to the start of the band
+                out.push('$')
+            }
+
+            first = false;
+
+            let _ = write!(out, "#{}", color);
+            run_length(&mut out, &sixels);
+        }
+
+        out.push('-')
+    }
+
+    out.push_str("\x1b\\");
+
+    out
+}
+
+/// Appends the sixels, repeating those that appear multiple times in a row.
+fn run_length(out: &mut String, sixels: &[u8]) {
+    let mut rest = sixels;
+
+    while let Some(&sixel) = rest.first() {
+        let count = rest.iter().take_while(|s| **s == sixel).count();
+        let symbol = char::from(63 + sixel);
+
+        if count > 3 {
+            let _ = write!(out, "!{}{}", count, symbol);
+        } else {
+            (0..count).for_each(|_| out.push(symbol))
+        }
+
+        rest = &rest[count..]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn encode() {
+        let red = [255, 0, 0];
+        let blue = [0, 0, 255];
+        let rgb = [red, red, red, red, blue, red, red, red, red, red]
+            .iter()
+            .flatten()
+            .copied()
+            .collect();
+
+        let pixels = Pixels::new(5, 2, rgb).unwrap();
+
+        assert_eq!(
+            super::encode(&pixels),
+            "\x1bPq\"1;1;5;2#5;2;0;0;100#180;2;100;0;0#5!4?@$#180!4BA-\x1b\\"
+        );
+    }
+}
@@ -1,6 +1,7 @@
 mod clear;
 mod container;
 mod fit;
+pub mod image;
 pub mod input;
 pub mod progress;
 mod scroll;
@@ -11,6 +12,7 @@ pub use {
     clear::*,
     container::*,
     fit::*,
+    image::{Image, Pixels},
     progress::ProgressBar,
     scroll::*,
     spinner::*,
@@ -69,6 +69,13 @@ const DEFAULT_BINDS: &[(Key, Command)] = {
             },
             TabExplore,
         ),
+        (
+            Key {
+                code: Char('q'),
+                modifiers: M::NONE,
+            },
+            TabQueue,
+        ),
         (
             Key {
                 code: Tab,
@@ -160,6 +167,90 @@ const DEFAULT_BINDS: &[(Key, Command)] = {
             },
             SkipBack,
         ),
+        (
+            Key {
+                code: Char('a'),
+                modifiers: M::NONE,
+            },
+            SaveToLibrary,
+        ),
+        (
+            Key {
+                code: Delete,
+                modifiers: M::NONE,
+            },
+            RemoveFromLibrary,
+        ),
+        (
+            Key {
+                code: Char('u'),
+                modifiers: M::NONE,
+            },
+            AppendToQueue,
+        ),
+        (
+            Key {
+                code: Char('p'),
+                modifiers: M::NONE,
+            },
+            PlayNext,
+        ),
+        (
+            Key {
+                code: Backspace,
+                modifiers: M::NONE,
+            },
+            RemoveFromQueue,
+        ),
+        (
+            Key {
+                code: Char('z'),
+                modifiers: M::NONE,
+            },
+            ToggleShuffle,
+        ),
+        (
+            Key {
+                code: Char('y'),
+                modifiers: M::NONE,
+            },
+            CycleRepeat,
+        ),
+        (
+            Key {
+                code: Char('d'),
+                modifiers: M::NONE,
+            },
+            DownloadTrack,
+        ),
+        (
+            Key {
+                code: Char('D'),
+                modifiers: M::SHIFT,
+            },
+            DownloadRelease,
+        ),
+        (
+            Key {
+                code: Char('f'),
+                modifiers: M::NONE,
+            },
+            CycleSearchFilter,
+        ),
+        (
+            Key {
+                code: Char('o'),
+                modifiers: M::NONE,
+            },
+            CycleDiscoverSort,
+        ),
+        (
+            Key {
+                code: Char('L'),
+                modifiers: M::SHIFT,
+            },
+            ToggleLyrics,
+        ),
     ]
 };
 
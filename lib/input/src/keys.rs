@@ -53,7 +53,7 @@ impl fmt::Display for Key {
             PageDown => string::PAGE_DOWN,
             Tab => string::TAB,
             BackTab => string::BACK_TAB,
-            Delete => string::DOWN,
+            Delete => string::DELETE,
             Insert => string::INSERT,
             Null => string::NULL,
             Esc => string::ESC,
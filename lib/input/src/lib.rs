@@ -17,6 +17,7 @@ pub enum Command {
     FocusSearchBar,
     TabLibrary,
     TabExplore,
+    TabQueue,
     CycleTabs,
     SelectionUp,
     SelectionDown,
@@ -30,6 +31,18 @@ pub enum Command {
     PrevTrack,
     SkipAhead,
     SkipBack,
+    SaveToLibrary,
+    RemoveFromLibrary,
+    AppendToQueue,
+    PlayNext,
+    RemoveFromQueue,
+    ToggleShuffle,
+    CycleRepeat,
+    DownloadTrack,
+    DownloadRelease,
+    CycleSearchFilter,
+    CycleDiscoverSort,
+    ToggleLyrics,
 }
 
 pub fn keys() -> impl Iterator<Item = Key> {
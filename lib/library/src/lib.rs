@@ -0,0 +1,271 @@
+//! A local collection of saved releases, outlets and fans.
+
+use {
+    bandcamp_api::data::{
+        common::Id,
+        fans::{Fan, FanArgs},
+        outlets::{Outlet, OutletKind},
+        releases::{Release, ReleaseArgs, ReleaseKind},
+    },
+    serde::{Deserialize, Serialize},
+    snafu::{ResultExt, Snafu},
+    std::{fs, io, path::Path},
+};
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("error reading library: {}", source))]
+    Read { source: io::Error },
+    #[snafu(display("error parsing library: {}", source))]
+    Parse { source: toml::de::Error },
+    #[snafu(display("error serializing library: {}", source))]
+    Encode { source: toml::ser::Error },
+    #[snafu(display("error writing library: {}", source))]
+    Write { source: io::Error },
+}
+
+pub type Result<T = ()> = std::result::Result<T, Error>;
+
+/// A collection of saved entries that can be stored on disk.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Library {
+    #[serde(default, rename = "entry")]
+    entries: Vec<Entry>,
+}
+
+impl Library {
+    /// Loads a library from the provided file, or returns an empty
+    /// one if the file doesn't exist yet.
+    pub fn load(file: impl AsRef<Path>) -> Result<Library> {
+        if file.as_ref().exists() {
+            let toml = fs::read_to_string(file).context(Read)?;
+            toml::from_str(&toml).context(Parse)
+        } else {
+            Ok(<_>::default())
+        }
+    }
+
+    /// Writes the library to the provided file, creating its
+    /// parent directories if necessary.
+    pub fn save(&self, file: impl AsRef<Path>) -> Result {
+        if let Some(dir) = file.as_ref().parent() {
+            fs::create_dir_all(dir).context(Write)?
+        }
+
+        let toml = toml::to_string_pretty(self).context(Encode)?;
+
+        fs::write(file, toml).context(Write)
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// Returns the entries matching the provided filter.
+    /// An empty filter matches every entry.
+    pub fn filtered(&self, filter: &str) -> impl Iterator<Item = &Entry> + '_ {
+        let filter = filter.to_lowercase();
+
+        self.entries
+            .iter()
+            .filter(move |entry| entry.matches(&filter))
+    }
+
+    pub fn contains(&self, entry: &Entry) -> bool {
+        self.entries.iter().any(|e| e.same_as(entry))
+    }
+
+    /// Adds an entry to the library, unless it's already in there.
+    /// Returns whether it was added.
+    pub fn add(&mut self, entry: Entry) -> bool {
+        if self.contains(&entry) {
+            false
+        } else {
+            self.entries.push(entry);
+            true
+        }
+    }
+
+    /// Removes an entry from the library.
+    /// Returns whether it was in there.
+    pub fn remove(&mut self, entry: &Entry) -> bool {
+        let len = self.entries.len();
+
+        self.entries.retain(|e| !e.same_as(entry));
+
+        len != self.entries.len()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum Entry {
+    Release(SavedRelease),
+    Outlet(SavedOutlet),
+    Fan(SavedFan),
+}
+
+impl Entry {
+    /// Returns whether both entries refer to the same thing,
+    /// regardless of any differing names.
+    pub fn same_as(&self, other: &Entry) -> bool {
+        match (self, other) {
+            (Entry::Release(a), Entry::Release(b)) => a.id == b.id,
+            (Entry::Outlet(a), Entry::Outlet(b)) => a.id == b.id,
+            (Entry::Fan(a), Entry::Fan(b)) => a.id == b.id,
+            _ => false,
+        }
+    }
+
+    /// Returns whether any of the names of this entry contain the filter.
+    /// Expects the filter to be lowercase.
+    fn matches(&self, filter: &str) -> bool {
+        let contains = |s: &str| s.to_lowercase().contains(filter);
+
+        match self {
+            Entry::Release(r) => contains(&r.title) || contains(&r.artist),
+            Entry::Outlet(o) => contains(&o.name),
+            Entry::Fan(f) => contains(&f.name),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedRelease {
+    pub id: Id<Release>,
+    pub kind: ReleaseKind,
+    pub outlet: Id<Outlet>,
+    pub title: String,
+    pub artist: String,
+}
+
+impl SavedRelease {
+    pub fn args(&self) -> ReleaseArgs {
+        ReleaseArgs {
+            id: self.id,
+            kind: self.kind,
+            outlet: self.outlet,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedOutlet {
+    pub id: Id<Outlet>,
+    pub kind: OutletKind,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedFan {
+    pub id: Id<Fan>,
+    pub name: String,
+}
+
+impl SavedFan {
+    pub fn args(&self) -> FanArgs {
+        FanArgs {
+            id: self.id,
+            start: 0,
+            count: FanArgs::DEFAULT_COUNT,
+        }
+    }
+}
+
+impl From<&Release> for SavedRelease {
+    fn from(release: &Release) -> Self {
+        SavedRelease {
+            id: release.info.id,
+            kind: release.info.kind,
+            outlet: release.info.band.id,
+            title: release.info.title.clone(),
+            artist: release.info.artist.clone(),
+        }
+    }
+}
+
+impl From<&Release> for Entry {
+    fn from(release: &Release) -> Self {
+        Entry::Release(release.into())
+    }
+}
+
+impl From<&Outlet> for Entry {
+    fn from(outlet: &Outlet) -> Self {
+        Entry::Outlet(SavedOutlet {
+            id: outlet.info.id,
+            kind: outlet.info.kind,
+            name: outlet.info.name.clone(),
+        })
+    }
+}
+
+impl From<&Fan> for Entry {
+    fn from(fan: &Fan) -> Self {
+        Entry::Fan(SavedFan {
+            id: fan.id,
+            name: fan.name.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const LIBRARY: &str = r#"
+        [[entry]]
+        type = "release"
+        id = 1
+        kind = "album"
+        outlet = 2
+        title = "Some Album"
+        artist = "Some Artist"
+
+        [[entry]]
+        type = "outlet"
+        id = 2
+        kind = "artist"
+        name = "Some Artist"
+
+        [[entry]]
+        type = "fan"
+        id = 3
+        name = "someone"
+    "#;
+
+    fn library() -> Library {
+        toml::from_str(LIBRARY).unwrap()
+    }
+
+    #[test]
+    fn roundtrip() {
+        let library = library();
+        let toml = toml::to_string(&library).unwrap();
+
+        assert_eq!(
+            library.entries(),
+            toml::from_str::<Library>(&toml).unwrap().entries()
+        )
+    }
+
+    #[test]
+    fn filter() {
+        let library = library();
+
+        assert_eq!(library.filtered("").count(), 3);
+        assert_eq!(library.filtered("some artist").count(), 2);
+        assert_eq!(library.filtered("ALBUM").count(), 1);
+        assert_eq!(library.filtered("nothing").count(), 0);
+    }
+
+    #[test]
+    fn no_duplicates() {
+        let mut library = library();
+        let entry = library.entries()[0].clone();
+
+        assert!(!library.add(entry.clone()));
+        assert!(library.remove(&entry));
+        assert!(library.add(entry));
+    }
+}
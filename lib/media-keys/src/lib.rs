@@ -1,27 +1,72 @@
-#[cfg(not(windows))]
+#[cfg(not(any(windows, target_os = "linux")))]
 mod none;
 
+#[cfg(target_os = "linux")]
+mod mpris;
+
 #[cfg(windows)]
 mod windows;
 
+use std::time::Duration;
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum MediaKey {
     PlayPause,
+    Play,
+    Pause,
     Stop,
     NextTrack,
     PrevTrack,
+    SeekForward(Duration),
+    SeekBack(Duration),
+    SetPosition(Duration),
+}
+
+/// What's currently playing, for platforms that can display it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Playback {
+    pub status: Status,
+    pub track: Option<Metadata>,
+    pub position: Duration,
+    pub volume: f32,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum Status {
+    Playing,
+    Paused,
+    #[default]
+    Stopped,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Metadata {
+    /// The id of the release the track is from, which together with
+    /// the track's position on it distinguishes it from any other one.
+    pub release: u64,
+    pub number: usize,
+    pub title: String,
+    pub artist: String,
+    pub album: String,
+    pub length: Duration,
 }
 
 #[cfg(windows)]
 type Inner = windows::Listener;
 
-#[cfg(not(windows))]
+#[cfg(target_os = "linux")]
+type Inner = mpris::Listener;
+
+#[cfg(not(any(windows, target_os = "linux")))]
 type Inner = none::None;
 
 #[cfg(windows)]
 pub type Error = windows::Error;
 
-#[cfg(not(windows))]
+#[cfg(target_os = "linux")]
+pub type Error = mpris::Error;
+
+#[cfg(not(any(windows, target_os = "linux")))]
 pub type Error = none::Error;
 
 pub struct Listener(Inner);
@@ -34,4 +79,10 @@ impl Listener {
     pub fn keys(&self) -> impl Iterator<Item = MediaKey> + '_ {
         self.0.keys()
     }
+
+    /// Lets the system know what's currently playing.
+    /// No effect on platforms that don't support displaying it.
+    pub fn publish(&self, playback: &Playback) {
+        self.0.publish(playback)
+    }
 }
@@ -0,0 +1,514 @@
+//! On Linux, we register as an MPRIS media player on the session bus,
+//! which is how desktop environments, `playerctl` and the like find out
+//! what's playing and how they tell us what to do.
+//!
+//! The D-Bus connection lives on its own thread. Method calls are turned
+//! into [MediaKey](MediaKey)s and sent back, while the current
+//! [Playback](Playback) is sent to it to be served as properties.
+
+use {
+    crate::{MediaKey, Metadata, Playback, Status},
+    dbus::{
+        arg::{PropMap, RefArg, Variant},
+        blocking::{
+            stdintf::org_freedesktop_dbus::{PropertiesPropertiesChanged, RequestNameReply},
+            Connection,
+        },
+        channel::{MatchingReceiver, Sender},
+        message::{MatchRule, SignalArgs},
+        Message,
+        Path,
+    },
+    dbus_crossroads::{Crossroads, IfaceBuilder, MethodErr},
+    std::{
+        process,
+        sync::{
+            mpsc::{self, TryRecvError},
+            Arc,
+            Mutex,
+            MutexGuard,
+        },
+        thread,
+        time::{Duration, Instant},
+    },
+};
+
+pub type Error = std::convert::Infallible;
+
+const NAME: &str = "org.mpris.MediaPlayer2.justbc";
+const PATH: &str = "/org/mpris/MediaPlayer2";
+const ROOT_IFACE: &str = "org.mpris.MediaPlayer2";
+const PLAYER_IFACE: &str = "org.mpris.MediaPlayer2.Player";
+const TRACK_PATH: &str = "/io/github/reslario/justbc/track";
+
+/// How long to wait for incoming messages before checking
+/// whether the playback has changed.
+const INTERVAL: Duration = Duration::from_millis(50);
+
+/// How far the position may differ from where it would be
+/// after playing normally before we consider it a seek.
+const SEEK_TOLERANCE: Duration = Duration::from_secs(1);
+
+pub struct Listener {
+    keys: mpsc::Receiver<MediaKey>,
+    playback: mpsc::Sender<Playback>,
+}
+
+impl Listener {
+    pub fn new() -> Result<Listener, Error> {
+        // not having a session bus shouldn't keep anyone from
+        // listening to music, so we just don't serve anything then
+        Ok(Connection::new_session()
+            .and_then(Listener::serve)
+            .unwrap_or_else(|_| Listener::inactive()))
+    }
+
+    fn inactive() -> Listener {
+        Listener {
+            keys: mpsc::channel().1,
+            playback: mpsc::channel().0,
+        }
+    }
+
+    fn serve(conn: Connection) -> Result<Listener, dbus::Error> {
+        if conn.request_name(NAME, false, false, true)? != RequestNameReply::PrimaryOwner {
+            // another instance is already running
+            let name = format!("{}.instance{}", NAME, process::id());
+            conn.request_name(name, false, false, true)?;
+        }
+
+        let (key_sender, keys) = mpsc::channel();
+        let (playback, updates) = mpsc::channel();
+
+        let current = Arc::new(Mutex::new(Playback::default()));
+
+        let mut cr = Crossroads::new();
+        let ifaces = [root_iface(&mut cr), player_iface(&mut cr)];
+        cr.insert(PATH, &ifaces, Player {
+            playback: current.clone(),
+            keys: key_sender,
+        });
+
+        conn.start_receive(
+            MatchRule::new_method_call(),
+            Box::new(move |msg, conn| {
+                let _ = cr.handle_message(msg, conn);
+                true
+            }),
+        );
+
+        thread::spawn(move || Server::new(conn, current).run(updates));
+
+        Ok(Listener { keys, playback })
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = MediaKey> + '_ {
+        self.keys.try_iter()
+    }
+
+    pub fn publish(&self, playback: &Playback) {
+        // the server might have shut down due to a connection error,
+        // in which case there's no one to tell anymore
+        let _ = self.playback.send(playback.clone());
+    }
+}
+
+struct Server {
+    conn: Connection,
+    current: Arc<Mutex<Playback>>,
+    updated: Instant,
+}
+
+impl Server {
+    fn new(conn: Connection, current: Arc<Mutex<Playback>>) -> Server {
+        Server {
+            conn,
+            current,
+            updated: Instant::now(),
+        }
+    }
+
+    /// Handles incoming messages until either the connection
+    /// fails or the [Listener](Listener) is dropped.
+    fn run(mut self, updates: mpsc::Receiver<Playback>) {
+        loop {
+            if self.conn.process(INTERVAL).is_err() {
+                return
+            }
+
+            let mut latest = None;
+
+            loop {
+                match updates.try_recv() {
+                    Ok(playback) => latest = Some(playback),
+                    Err(TryRecvError::Empty) => break,
+                    Err(TryRecvError::Disconnected) => return,
+                }
+            }
+
+            if let Some(playback) = latest {
+                self.update(playback)
+            }
+        }
+    }
+
+    fn update(&mut self, new: Playback) {
+        let now = Instant::now();
+        let old = std::mem::replace(&mut *lock(&self.current), new.clone());
+
+        let mut changed = PropMap::new();
+
+        if old.status != new.status {
+            changed.insert(
+                "PlaybackStatus".into(),
+                variant(status(new.status).to_owned()),
+            );
+        }
+
+        if old.track != new.track {
+            changed.insert("Metadata".into(), variant(metadata(new.track.as_ref())));
+        }
+
+        if (old.volume - new.volume).abs() > f32::EPSILON {
+            changed.insert("Volume".into(), variant(f64::from(new.volume)));
+        }
+
+        if !changed.is_empty() {
+            let signal = PropertiesPropertiesChanged {
+                interface_name: PLAYER_IFACE.into(),
+                changed_properties: changed,
+                invalidated_properties: vec![],
+            };
+
+            let _ = self.conn.send(signal.to_emit_message(&Path::from(PATH)));
+        }
+
+        if old.track == new.track && seeked(&old, &new, now - self.updated) {
+            let signal = Message::new_signal(PATH, PLAYER_IFACE, "Seeked")
+                .unwrap()
+                .append1(micros(new.position));
+
+            let _ = self.conn.send(signal);
+        }
+
+        self.updated = now;
+    }
+}
+
+/// Returns whether the position changed by something other than playing
+/// for the provided duration since the last update.
+fn seeked(old: &Playback, new: &Playback, since: Duration) -> bool {
+    let expected = if old.status == Status::Playing {
+        old.position + since
+    } else {
+        old.position
+    };
+
+    expected.abs_diff(new.position) > SEEK_TOLERANCE
+}
+
+/// The data backing the MPRIS object.
+struct Player {
+    playback: Arc<Mutex<Playback>>,
+    keys: mpsc::Sender<MediaKey>,
+}
+
+impl Player {
+    fn playback(&self) -> MutexGuard<'_, Playback> {
+        lock(&self.playback)
+    }
+
+    fn send(&self, key: MediaKey) -> Result<(), MethodErr> {
+        // if the listener was dropped, we're about to shut down anyway
+        let _ = self.keys.send(key);
+        Ok(())
+    }
+}
+
+fn lock(playback: &Mutex<Playback>) -> MutexGuard<'_, Playback> {
+    // the data is replaced as a whole, so it can't be left
+    // in an inconsistent state by a panic
+    playback.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn root_iface(cr: &mut Crossroads) -> dbus_crossroads::IfaceToken<Player> {
+    cr.register(ROOT_IFACE, |b: &mut IfaceBuilder<Player>| {
+        b.method("Raise", (), (), |_, _, ()| Ok(()));
+        b.method("Quit", (), (), |_, _, ()| Ok(()));
+        b.property("CanQuit").get(|_, _| Ok(false));
+        b.property("CanRaise").get(|_, _| Ok(false));
+        b.property("HasTrackList").get(|_, _| Ok(false));
+        b.property("Identity").get(|_, _| Ok("justbc".to_owned()));
+        b.property("SupportedUriSchemes")
+            .get(|_, _| Ok(Vec::<String>::new()));
+        b.property("SupportedMimeTypes")
+            .get(|_, _| Ok(Vec::<String>::new()));
+    })
+}
+
+fn player_iface(cr: &mut Crossroads) -> dbus_crossroads::IfaceToken<Player> {
+    cr.register(PLAYER_IFACE, |b: &mut IfaceBuilder<Player>| {
+        b.signal::<(i64,), _>("Seeked", ("Position",));
+
+        b.method("Next", (), (), |_, p: &mut Player, ()| {
+            p.send(MediaKey::NextTrack)
+        });
+        b.method("Previous", (), (), |_, p: &mut Player, ()| {
+            p.send(MediaKey::PrevTrack)
+        });
+        b.method("Pause", (), (), |_, p: &mut Player, ()| {
+            p.send(MediaKey::Pause)
+        });
+        b.method("PlayPause", (), (), |_, p: &mut Player, ()| {
+            p.send(MediaKey::PlayPause)
+        });
+        b.method("Stop", (), (), |_, p: &mut Player, ()| {
+            p.send(MediaKey::Stop)
+        });
+        b.method("Play", (), (), |_, p: &mut Player, ()| {
+            p.send(MediaKey::Play)
+        });
+        b.method(
+            "Seek",
+            ("Offset",),
+            (),
+            |_, p: &mut Player, (offset,): (i64,)| {
+                let by = Duration::from_micros(offset.unsigned_abs());
+
+                p.send(if offset < 0 {
+                    MediaKey::SeekBack(by)
+                } else {
+                    MediaKey::SeekForward(by)
+                })
+            },
+        );
+        b.method(
+            "SetPosition",
+            ("TrackId", "Position"),
+            (),
+            |_, p: &mut Player, (track, position): (Path<'static>, i64)| {
+                let position = Duration::from_micros(position.max(0) as u64);
+
+                // the spec says to ignore requests for tracks
+                // that aren't playing anymore, or positions past their end
+                let valid = p.playback().track.as_ref().is_some_and(|current| {
+                    track_path(current) == track && position <= current.length
+                });
+
+                if valid {
+                    p.send(MediaKey::SetPosition(position))
+                } else {
+                    Ok(())
+                }
+            },
+        );
+        b.method(
+            "OpenUri",
+            ("Uri",),
+            (),
+            |_, _, (_,): (String,)| -> Result<(), _> {
+                Err(MethodErr::failed("opening URIs is not supported"))
+            },
+        );
+
+        b.property("PlaybackStatus")
+            .get(|_, p| Ok(status(p.playback().status).to_owned()));
+        b.property("Metadata")
+            .get(|_, p| Ok(metadata(p.playback().track.as_ref())));
+        b.property("Volume")
+            .get(|_, p| Ok(f64::from(p.playback().volume)));
+        b.property("Position")
+            .emits_changed_false()
+            .get(|_, p| Ok(micros(p.playback().position)));
+        b.property("Rate").get(|_, _| Ok(1.));
+        b.property("MinimumRate").get(|_, _| Ok(1.));
+        b.property("MaximumRate").get(|_, _| Ok(1.));
+
+        for can in &[
+            "CanGoNext",
+            "CanGoPrevious",
+            "CanPlay",
+            "CanPause",
+            "CanSeek",
+            "CanControl",
+        ] {
+            b.property(*can).emits_changed_const().get(|_, _| Ok(true));
+        }
+    })
+}
+
+fn status(status: Status) -> &'static str {
+    match status {
+        Status::Playing => "Playing",
+        Status::Paused => "Paused",
+        Status::Stopped => "Stopped",
+    }
+}
+
+fn metadata(track: Option<&Metadata>) -> PropMap {
+    let mut map = PropMap::new();
+
+    if let Some(track) = track {
+        map.insert("mpris:trackid".into(), variant(track_path(track)));
+        map.insert("mpris:length".into(), variant(micros(track.length)));
+        map.insert("xesam:title".into(), variant(track.title.clone()));
+        map.insert("xesam:artist".into(), variant(vec![track.artist.clone()]));
+        map.insert("xesam:album".into(), variant(track.album.clone()));
+    } else {
+        // the spec requires a track id even when there's no track
+        map.insert(
+            "mpris:trackid".into(),
+            variant(Path::from("/org/mpris/MediaPlayer2/TrackList/NoTrack")),
+        );
+    }
+
+    map
+}
+
+fn track_path(track: &Metadata) -> Path<'static> {
+    Path::from(format!("{}/{}_{}", TRACK_PATH, track.release, track.number))
+}
+
+fn variant(arg: impl RefArg + 'static) -> Variant<Box<dyn RefArg>> {
+    Variant(Box::new(arg))
+}
+
+fn micros(duration: Duration) -> i64 {
+    duration.as_micros() as i64
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::*,
+        dbus::{blocking::stdintf::org_freedesktop_dbus::Properties, channel::Channel},
+        std::{
+            io::{BufRead, BufReader},
+            process::{Child, Command, Stdio},
+        },
+    };
+
+    /// A private session bus that's shut down when dropped.
+    struct Bus {
+        daemon: Child,
+        address: String,
+    }
+
+    impl Bus {
+        fn start() -> Bus {
+            let mut daemon = Command::new("dbus-daemon")
+                .args(["--session", "--nofork", "--print-address"])
+                .stdout(Stdio::piped())
+                .spawn()
+                .expect("dbus-daemon is needed to test the MPRIS server");
+
+            let mut address = String::new();
+
+            BufReader::new(daemon.stdout.as_mut().unwrap())
+                .read_line(&mut address)
+                .unwrap();
+
+            Bus {
+                daemon,
+                address: address.trim().into(),
+            }
+        }
+
+        fn connect(&self) -> Connection {
+            let mut channel = Channel::open_private(&self.address).unwrap();
+            channel.register().unwrap();
+            channel.into()
+        }
+    }
+
+    impl Drop for Bus {
+        fn drop(&mut self) {
+            let _ = self.daemon.kill();
+        }
+    }
+
+    fn next_key(listener: &Listener) -> Option<MediaKey> {
+        listener.keys.recv_timeout(Duration::from_secs(2)).ok()
+    }
+
+    fn track() -> Metadata {
+        Metadata {
+            release: 3,
+            number: 1,
+            title: "title".into(),
+            artist: "artist".into(),
+            album: "album".into(),
+            length: Duration::from_secs(60),
+        }
+    }
+
+    #[test]
+    fn serve() {
+        let bus = Bus::start();
+
+        let listener = Listener::serve(bus.connect()).unwrap();
+
+        listener.publish(&Playback {
+            status: Status::Playing,
+            track: Some(track()),
+            position: Duration::from_secs(10),
+            volume: 0.5,
+        });
+
+        let client = bus.connect();
+        let proxy = client.with_proxy(NAME, PATH, Duration::from_secs(2));
+
+        let call = |method: &str| {
+            proxy
+                .method_call::<(), _, _, _>(PLAYER_IFACE, method, ())
+                .unwrap()
+        };
+
+        call("PlayPause");
+        assert_eq!(next_key(&listener), Some(MediaKey::PlayPause));
+
+        call("Next");
+        assert_eq!(next_key(&listener), Some(MediaKey::NextTrack));
+
+        proxy
+            .method_call::<(), _, _, _>(PLAYER_IFACE, "Seek", (-5_000_000_i64,))
+            .unwrap();
+        assert_eq!(
+            next_key(&listener),
+            Some(MediaKey::SeekBack(Duration::from_secs(5)))
+        );
+
+        // give the server a moment to pick up the published playback
+        thread::sleep(INTERVAL * 4);
+
+        let status: String = proxy.get(PLAYER_IFACE, "PlaybackStatus").unwrap();
+        assert_eq!(status, "Playing");
+
+        let position: i64 = proxy.get(PLAYER_IFACE, "Position").unwrap();
+        assert_eq!(position, 10_000_000);
+
+        let metadata: PropMap = proxy.get(PLAYER_IFACE, "Metadata").unwrap();
+        assert_eq!(
+            metadata["xesam:title"].0.as_str(),
+            Some(track().title.as_str())
+        );
+
+        let set_position = |track: &str, position: i64| {
+            proxy
+                .method_call::<(), _, _, _>(
+                    PLAYER_IFACE,
+                    "SetPosition",
+                    (Path::from(track), position),
+                )
+                .unwrap()
+        };
+
+        // another track of the same release
+        set_position("/io/github/reslario/justbc/track/3_2", 1_000_000);
+        set_position("/io/github/reslario/justbc/track/3_1", 1_000_000);
+        assert_eq!(
+            next_key(&listener),
+            Some(MediaKey::SetPosition(Duration::from_secs(1)))
+        );
+    }
+}
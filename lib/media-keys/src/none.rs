@@ -9,4 +9,6 @@ impl None {
     pub fn keys(&self) -> impl Iterator<Item = crate::MediaKey> {
         std::iter::empty()
     }
+
+    pub fn publish(&self, _: &crate::Playback) {}
 }
@@ -80,6 +80,8 @@ impl Listener {
         std::iter::from_fn(move || self.get_message()).filter_map(key_from_msg)
     }
 
+    pub fn publish(&self, _: &crate::Playback) {}
+
     fn get_message(&self) -> Option<MSG> {
         let mut msg = MaybeUninit::uninit();
 
@@ -1,4 +1,4 @@
-use crate::span::FrameSpan;
+use {crate::span::FrameSpan, std::time::Duration};
 
 #[derive(Copy, Clone, Debug)]
 pub struct CachedFrame {
@@ -28,13 +28,25 @@ pub struct FrameCache {
 
 impl FrameCache {
     pub fn set(&mut self, index: usize, frame: CachedFrame) {
-        if index <= self.frames.len() {
+        if index >= self.frames.len() {
             self.frames.resize(index + 1, <_>::default())
         }
 
         self.frames[index] = frame
     }
 
+    /// Returns where the last decoded frame that starts
+    /// before the provided duration ends.
+    pub fn decoded_until(&self, duration: Duration) -> Duration {
+        self.frames
+            .iter()
+            .rev()
+            .filter(|frame| !frame.placeholder())
+            .find(|frame| frame.span.start_duration() <= duration)
+            .map(|frame| frame.span.end_duration())
+            .unwrap_or_default()
+    }
+
     pub fn enumerated<R>(
         &self,
         range: R,
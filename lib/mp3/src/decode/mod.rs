@@ -3,6 +3,7 @@ mod tracked;
 
 use {
     crate::{
+        header::Header,
         samples::{SampleBuf, Samples},
         Frame,
     },
@@ -16,12 +17,18 @@ use {
 };
 
 impl Frame {
-    fn new(samples: SampleBuf, info: minimp3::mp3dec_frame_info_t, pos: u64) -> Frame {
+    fn new(
+        samples: SampleBuf,
+        info: minimp3::mp3dec_frame_info_t,
+        pos: u64,
+        header: Option<Header>,
+    ) -> Frame {
         Frame {
             samples: Samples::new(samples),
             channels: info.channels as _,
             sample_rate: info.hz as _,
             pos,
+            header,
         }
     }
 }
@@ -48,15 +55,28 @@ impl<R: Read> Decoder<R> {
         }
     }
 
-    pub fn next_frame(&mut self, buf: SampleBuf) -> io::Result<Frame> {
+    /// Decodes the next frame into `buf`, looking for the header
+    /// describing the stream if it's the `first` one.
+    pub fn next_frame(&mut self, buf: SampleBuf, first: bool) -> io::Result<Frame> {
         self.buf.fill(&mut self.reader)?;
 
         let (samples, frame_info) = self.decode_frame(buf);
 
-        self.buf.consume(frame_info.frame_bytes as _);
+        // the buffer is as full as it can get, so
+        // there's nothing more to decode
+        if frame_info.frame_bytes == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into())
+        }
+
+        let header = match first {
+            true => Header::parse(&self.buf.as_slice()[frame_info.frame_offset as usize..]),
+            false => None,
+        };
+
         let pos = self.pos() + frame_info.frame_offset as u64;
+        self.buf.consume(frame_info.frame_bytes as _);
 
-        Ok(Frame::new(samples, frame_info, pos))
+        Ok(Frame::new(samples, frame_info, pos, header))
     }
 
     fn pos(&self) -> u64 {
@@ -67,7 +87,8 @@ impl<R: Read> Decoder<R> {
         &mut self,
         mut samples: SampleBuf,
     ) -> (SampleBuf, minimp3::mp3dec_frame_info_t) {
-        let mut frame_info = MaybeUninit::uninit();
+        // the decoder leaves the info as it is when it skips data
+        let mut frame_info = MaybeUninit::zeroed();
         let data = self.buf.as_slice();
         samples.set_max_len();
 
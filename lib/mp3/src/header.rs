@@ -0,0 +1,276 @@
+use std::convert::TryInto;
+
+/// The header encoders write into the first frame of an mp3 instead of
+/// audio, which describes the rest of the stream. This is either a Xing
+/// header, which is called Info in constant bitrate streams, or a VBRI
+/// header written by the Fraunhofer encoder.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Header {
+    /// The number of samples per channel in every frame.
+    pub frame_len: u16,
+    /// The number of audio frames, not counting the one holding the header.
+    pub frames: Option<u32>,
+    pub toc: Option<SeekTable>,
+    pub lame: Option<Lame>,
+}
+
+/// The number of samples the encoder added around the audio,
+/// as stored in the extension LAME and others write after the header.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Lame {
+    /// Silence at the start.
+    pub delay: u16,
+    /// Silence at the end, to fill up the last frame.
+    pub padding: u16,
+}
+
+/// Where frames spread evenly across the stream begin, in bytes
+/// from the start of the frame holding the header.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeekTable {
+    frames_per_entry: f64,
+    offsets: Vec<u64>,
+    /// The size of the whole stream.
+    bytes: u64,
+}
+
+impl Header {
+    const FRAMES: u32 = 1;
+    const BYTES: u32 = 2;
+    const TOC: u32 = 4;
+    const QUALITY: u32 = 8;
+
+    /// Reads the header from the bytes of a whole frame, if it has one.
+    pub fn parse(frame: &[u8]) -> Option<Header> {
+        let header = frame.get(..4)?;
+
+        let mpeg1 = header[1] >> 3 & 0b11 == 0b11;
+        let mono = header[3] >> 6 == 0b11;
+        let crc = header[1] & 1 == 0;
+
+        let side_info = match (mpeg1, mono) {
+            (true, false) => 32,
+            (true, true) | (false, false) => 17,
+            (false, true) => 9,
+        };
+
+        let frame_len = if mpeg1 { 1152 } else { 576 };
+
+        let xing = frame.get(4 + 2 * crc as usize + side_info..);
+
+        // VBRI headers are always in the same place
+        xing.and_then(|tag| Header::xing(tag, frame_len))
+            .or_else(|| Header::vbri(frame.get(4 + 32..)?, frame_len))
+    }
+
+    fn xing(mut tag: &[u8], frame_len: u16) -> Option<Header> {
+        if !matches!(take(&mut tag, 4)?, b"Xing" | b"Info") {
+            return None
+        }
+
+        let flags = read_u32(&mut tag)?;
+
+        let mut field = |flag, len| match flags & flag {
+            0 => Some(None),
+            _ => take(&mut tag, len).map(Some),
+        };
+
+        let frames = field(Header::FRAMES, 4)?.map(be_u32);
+        let bytes = field(Header::BYTES, 4)?.map(be_u32);
+        let toc = field(Header::TOC, 100)?;
+        field(Header::QUALITY, 4)?;
+
+        let toc = match (frames, bytes, toc) {
+            // without any frames there's nothing to seek to
+            (Some(0), ..) => None,
+            (Some(frames), Some(bytes), Some(toc)) => Some(SeekTable {
+                frames_per_entry: f64::from(frames) / 100.,
+                offsets: toc
+                    .iter()
+                    .map(|offset| u64::from(*offset) * u64::from(bytes) / 256)
+                    .collect(),
+                bytes: bytes.into(),
+            }),
+            _ => None,
+        };
+
+        Some(Header {
+            frame_len,
+            frames,
+            toc,
+            lame: Lame::parse(tag),
+        })
+    }
+
+    fn vbri(mut tag: &[u8], frame_len: u16) -> Option<Header> {
+        if take(&mut tag, 4)? != b"VBRI" {
+            return None
+        }
+
+        // version, delay and quality
+        take(&mut tag, 6)?;
+
+        let bytes = read_u32(&mut tag)?;
+        let frames = read_u32(&mut tag)?;
+        let entries = read_u16(&mut tag)?;
+        let scale = read_u16(&mut tag)?;
+        let entry_size = read_u16(&mut tag)?;
+        let frames_per_entry = read_u16(&mut tag)?;
+
+        let mut offsets = vec![0];
+        let mut offset = 0;
+
+        for _ in 0..entries {
+            let entry = take(&mut tag, entry_size.into())?
+                .iter()
+                .fold(0, |entry, byte| entry << 8 | u64::from(*byte));
+
+            offset += entry * u64::from(scale);
+            offsets.push(offset);
+        }
+
+        Some(Header {
+            frame_len,
+            frames: Some(frames),
+            toc: Some(SeekTable {
+                frames_per_entry: frames_per_entry.max(1).into(),
+                offsets,
+                bytes: bytes.into(),
+            }),
+            lame: None,
+        })
+    }
+}
+
+impl Lame {
+    fn parse(tag: &[u8]) -> Option<Lame> {
+        // the extension starts with the name of the encoder,
+        // but all of them use the same layout
+        if tag.first().copied().unwrap_or_default() == 0 {
+            return None
+        }
+
+        let bytes = tag.get(21..24)?;
+        let (a, b, c) = (
+            u16::from(bytes[0]),
+            u16::from(bytes[1]),
+            u16::from(bytes[2]),
+        );
+
+        Some(Lame {
+            delay: a << 4 | b >> 4,
+            padding: (b & 0xF) << 8 | c,
+        })
+    }
+}
+
+impl SeekTable {
+    /// Estimates where the frame with the provided index begins,
+    /// in bytes from the start of the frame holding the header.
+    pub fn offset(&self, frame: u64) -> u64 {
+        let entry = frame as f64 / self.frames_per_entry;
+        let index = entry.floor() as usize;
+
+        let offset = |index| self.offsets.get(index).copied().unwrap_or(self.bytes);
+        let (start, end) = (offset(index), offset(index + 1).max(offset(index)));
+
+        start + ((end - start) as f64 * entry.fract()) as u64
+    }
+}
+
+fn take<'a>(bytes: &mut &'a [u8], len: usize) -> Option<&'a [u8]> {
+    let taken = bytes.get(..len)?;
+    *bytes = &bytes[len..];
+
+    Some(taken)
+}
+
+fn be_u32(bytes: &[u8]) -> u32 {
+    bytes
+        .iter()
+        .fold(0, |int, byte| int << 8 | u32::from(*byte))
+}
+
+fn read_u32(bytes: &mut &[u8]) -> Option<u32> {
+    take(bytes, 4)?.try_into().ok().map(u32::from_be_bytes)
+}
+
+fn read_u16(bytes: &mut &[u8]) -> Option<u16> {
+    take(bytes, 2)?.try_into().ok().map(u16::from_be_bytes)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// MPEG1 layer 3, 128 kbps, 44.1 kHz, joint stereo
+    const FRAME_HEADER: [u8; 4] = [0xFF, 0xFB, 0x90, 0x64];
+
+    #[test]
+    fn xing() {
+        let mut frame = FRAME_HEADER.to_vec();
+        frame.extend_from_slice(&[0; 32]);
+        frame.extend_from_slice(b"Info");
+        frame.extend_from_slice(&(Header::FRAMES | Header::BYTES | Header::TOC).to_be_bytes());
+        frame.extend_from_slice(&1000u32.to_be_bytes());
+        frame.extend_from_slice(&25600u32.to_be_bytes());
+        frame.extend((0..100).map(|i| i * 2));
+        frame.extend_from_slice(b"LAME3.100");
+        frame.extend_from_slice(&[0; 12]);
+        // a delay of 576 and padding of 1234
+        frame.extend_from_slice(&[0x24, 0x04, 0xD2]);
+        frame.resize(417, 0);
+
+        let header = Header::parse(&frame).unwrap();
+
+        assert_eq!(header.frame_len, 1152);
+        assert_eq!(header.frames, Some(1000));
+        assert_eq!(
+            header.lame,
+            Some(Lame {
+                delay: 576,
+                padding: 1234
+            })
+        );
+
+        let toc = header.toc.unwrap();
+        assert_eq!(toc.offset(0), 0);
+        assert_eq!(toc.offset(15), 300);
+        assert_eq!(toc.offset(995), 22700);
+
+        frame[44..48].copy_from_slice(&0u32.to_be_bytes());
+        let header = Header::parse(&frame).unwrap();
+        assert_eq!(header.frames, Some(0));
+        assert_eq!(header.toc, None);
+
+        frame[36..40].copy_from_slice(b"Nope");
+        assert_eq!(Header::parse(&frame), None);
+    }
+
+    #[test]
+    fn vbri() {
+        let mut frame = FRAME_HEADER.to_vec();
+        frame.extend_from_slice(&[0; 32]);
+        frame.extend_from_slice(b"VBRI");
+        frame.extend_from_slice(&[0, 1, 0x04, 0x81, 0, 75]);
+        frame.extend_from_slice(&30000u32.to_be_bytes());
+        frame.extend_from_slice(&100u32.to_be_bytes());
+        // 3 entries of 2 bytes, scaled by 10, each for 40 frames
+        for field in [3u16, 10, 2, 40].iter() {
+            frame.extend_from_slice(&field.to_be_bytes());
+        }
+        for entry in [1000u16, 1200, 800].iter() {
+            frame.extend_from_slice(&entry.to_be_bytes());
+        }
+
+        let header = Header::parse(&frame).unwrap();
+
+        assert_eq!(header.frames, Some(100));
+        assert_eq!(header.lame, None);
+
+        let toc = header.toc.unwrap();
+        assert_eq!(toc.offset(40), 10000);
+        assert_eq!(toc.offset(60), 16000);
+        assert_eq!(toc.offset(120), 30000);
+    }
+}
@@ -1,11 +1,14 @@
 mod cache;
 mod decode;
+mod header;
 mod samples;
 mod span;
+mod trim;
 
 use {
     cache::*,
     decode::*,
+    header::Header,
     samples::*,
     span::FrameSpan,
     std::{
@@ -13,6 +16,7 @@ use {
         mem,
         time::Duration,
     },
+    trim::Trim,
 };
 
 const MICROS_PER_SEC: u32 = Duration::from_secs(1).as_micros() as _;
@@ -22,11 +26,18 @@ pub struct Frame {
     pub channels: u16,
     pub sample_rate: u32,
     pub pos: u64,
+    /// The header describing the stream, if this frame holds one.
+    pub(crate) header: Option<Header>,
 }
 
 impl Frame {
     fn micros(&self) -> u32 {
-        let samples = self.samples.len() / self.channels;
+        // frames without any audio don't have channels either
+        let samples = self
+            .samples
+            .audible()
+            .checked_div(self.channels)
+            .unwrap_or_default();
 
         let secs = samples as f64 / self.sample_rate as f64;
 
@@ -36,12 +47,14 @@ impl Frame {
     }
 
     fn start_at(&mut self, duration: Duration) {
-        self.samples.set_current(self.samples_in(duration))
+        self.samples.seek(self.samples_in(duration))
     }
 
+    /// Returns the number of samples across all channels that
+    /// play for the provided duration.
     fn samples_in(&self, duration: Duration) -> u16 {
-        let samples = duration.as_secs_f64() / self.sample_rate as f64;
-        samples.round() as _
+        let samples = (duration.as_secs_f64() * self.sample_rate as f64).round();
+        samples as u16 * self.channels
     }
 }
 
@@ -67,6 +80,7 @@ fn empty_frame() -> Frame {
         sample_rate: 44100,
         channels: 2,
         pos: 0,
+        header: None,
     }
 }
 
@@ -76,6 +90,16 @@ pub struct Mp3<R: Read> {
     decoder: Decoder<R>,
     cache: FrameCache,
     current: Current,
+    info: Option<Info>,
+    trim: Option<Trim>,
+}
+
+/// What the header in the first frame says about the stream.
+struct Info {
+    header: Header,
+    sample_rate: u32,
+    /// Where the frame holding the header begins.
+    pos: u64,
 }
 
 impl<R: Read> Mp3<R> {
@@ -84,15 +108,39 @@ impl<R: Read> Mp3<R> {
             decoder: Decoder::new(reader),
             cache: <_>::default(),
             current: <_>::default(),
+            info: None,
+            trim: None,
         }
     }
 
     fn next_frame(&mut self) -> Result<(), io::Error> {
         self.current.frame = {
             let samples = mem::take(&mut self.current.frame.samples);
-            self.decoder.next_frame(samples.into_buf())?
+            let first = self.current.frame_index == 0;
+            self.decoder.next_frame(samples.into_buf(), first)?
         };
 
+        let frame = &mut self.current.frame;
+
+        match (self.current.frame_index, frame.header.take()) {
+            // the header decodes to a frame of silence
+            (0, Some(header)) => {
+                self.trim = Trim::new(&header);
+                self.info = Some(Info {
+                    header,
+                    sample_rate: frame.sample_rate,
+                    pos: frame.pos,
+                });
+                frame.samples.truncate(0)
+            }
+            (0, None) => (),
+            (index, _) => {
+                if let Some(trim) = self.trim {
+                    trim.apply(frame, index as u64 - 1)
+                }
+            }
+        }
+
         self.current.span = FrameSpan::new(self.current.span.end(), self.current.frame.micros());
 
         let cached = CachedFrame {
@@ -111,10 +159,14 @@ impl<R: Read> Iterator for Mp3<R> {
     type Item = i16;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.current.frame.samples.next().or_else(|| {
-            self.next_frame().ok()?;
-            self.current.frame.samples.next()
-        })
+        loop {
+            // frames can be left without any samples by trimming
+            // or when the decoder is still getting started
+            match self.current.frame.samples.next() {
+                Some(sample) => return Some(sample),
+                None => self.next_frame().ok()?,
+            }
+        }
     }
 }
 
@@ -132,7 +184,16 @@ impl<R: Read> rodio::Source for Mp3<R> {
     }
 
     fn total_duration(&self) -> Option<Duration> {
-        None
+        let info = self.info.as_ref().filter(|info| info.sample_rate > 0)?;
+
+        let samples = match self.trim {
+            Some(trim) => trim.len()?,
+            None => u64::from(info.header.frames?) * u64::from(info.header.frame_len),
+        };
+
+        Some(Duration::from_secs_f64(
+            samples as f64 / f64::from(info.sample_rate),
+        ))
     }
 }
 
@@ -147,6 +208,8 @@ impl<R: Read + Seek> seek::SeekableSource for Mp3<R> {
             Ok(duration)
         } else if self.current.span.start_duration() > duration {
             self.find_left(duration)
+        } else if self.cache.decoded_until(duration) + Self::JUMP < duration {
+            self.jump(duration)
         } else {
             self.find_right(duration)
         }
@@ -154,6 +217,76 @@ impl<R: Read + Seek> seek::SeekableSource for Mp3<R> {
 }
 
 impl<R: Read + Seek> Mp3<R> {
+    /// How far past the decoded frames a position has to be for seeking to
+    /// jump there directly, rather than decoding everything before it.
+    const JUMP: Duration = Duration::from_secs(10);
+
+    /// The loudest a sample can be while still counting as silence.
+    const SILENCE: i16 = i16::MAX / 100;
+
+    /// Returns whether the audio is silent for the provided duration at its
+    /// start, by decoding it ahead of time. Playback starts at the beginning
+    /// afterwards, so this is only useful before anything was played.
+    pub fn starts_silent(&mut self, len: Duration) -> bool {
+        use seek::SeekableSource;
+
+        // the first sample decides the sample rate
+        let first = match self.next() {
+            Some(sample) => sample,
+            None => return true,
+        };
+
+        let len = self.current.frame.samples_in(len);
+
+        let silent = std::iter::once(first)
+            .chain(self.by_ref())
+            .take(len.into())
+            .all(|sample| sample.saturating_abs() <= Self::SILENCE);
+
+        // if this fails, so will playing the audio
+        let _ = self.seek(Duration::ZERO);
+
+        silent
+    }
+
+    /// Continues decoding right before `duration`, estimating where that
+    /// is in the stream from the seek table in the header, if there is one.
+    fn jump(&mut self, duration: Duration) -> Result<Duration, io::Error> {
+        let info = match &self.info {
+            Some(info) if info.sample_rate > 0 => info,
+            _ => return self.find_right(duration),
+        };
+
+        let toc = match &info.header.toc {
+            Some(toc) => toc,
+            None => return self.find_right(duration),
+        };
+
+        let rate = f64::from(info.sample_rate);
+        let frame_len = u64::from(info.header.frame_len);
+        let skipped = self.trim.map(|trim| trim.start()).unwrap_or_default();
+
+        // counting from the first frame after the header
+        let index = ((duration.as_secs_f64() * rate) as u64 + skipped) / frame_len;
+        let start = (index * frame_len).saturating_sub(skipped) as f64 / rate;
+
+        self.decoder
+            .seek(io::SeekFrom::Start(info.pos + toc.offset(index)))?;
+
+        self.current.frame_index = index as usize + 1;
+        self.current.span = FrameSpan::new((start * MICROS_PER_SEC as f64) as u32, 0);
+
+        while self.current.span.end_duration() <= duration {
+            self.next_frame()?
+        }
+
+        self.current
+            .frame
+            .start_at(duration.saturating_sub(self.current.span.start_duration()));
+
+        Ok(duration)
+    }
+
     fn find_left(&mut self, duration: Duration) -> Result<Duration, io::Error> {
         let (index, frame) = self
             .cache
@@ -162,7 +295,7 @@ impl<R: Read + Seek> Mp3<R> {
             .unwrap_or_default();
 
         self.current.frame_index = index;
-        self.current.span = frame.span.shift_back();
+        self.current.span = frame.span.preceding();
 
         self.decoder.seek(io::SeekFrom::Start(frame.pos))?;
 
@@ -211,3 +344,125 @@ impl<R: Read + Seek> Mp3<R> {
         Ok(duration)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use {super::*, rodio::Source, seek::SeekableSource, std::io::Cursor};
+
+    /// MPEG1 layer 3, 128 kbps, 44.1 kHz, joint stereo
+    const FRAME_HEADER: [u8; 4] = [0xFF, 0xFB, 0x90, 0x64];
+    const FRAME_BYTES: usize = 417;
+    const FRAME_LEN: u64 = 1152;
+
+    fn frame(tag: &[u8]) -> Vec<u8> {
+        let mut frame = FRAME_HEADER.to_vec();
+        frame.extend_from_slice(&[0; 32]);
+        frame.extend_from_slice(tag);
+        frame.resize(FRAME_BYTES, 0);
+        frame
+    }
+
+    /// An Info header for `frames` frames of silence with a seek table,
+    /// as well as the encoder delay and padding if provided.
+    fn info(frames: u32, lame: Option<[u8; 3]>) -> Vec<u8> {
+        let mut tag = b"Info".to_vec();
+        // the number of frames and bytes, and the seek table
+        tag.extend_from_slice(&7u32.to_be_bytes());
+        tag.extend_from_slice(&frames.to_be_bytes());
+        tag.extend_from_slice(&((frames + 1) * FRAME_BYTES as u32).to_be_bytes());
+        tag.extend((0..100).map(|i| (i * 256 / 100) as u8));
+
+        if let Some(lame) = lame {
+            tag.extend_from_slice(b"LAME3.100");
+            tag.extend_from_slice(&[0; 12]);
+            tag.extend_from_slice(&lame);
+        }
+
+        frame(&tag)
+    }
+
+    fn stream(header: Vec<u8>, frames: usize) -> Mp3<Cursor<Vec<u8>>> {
+        let mut stream = header;
+
+        for _ in 0..frames {
+            stream.extend(frame(&[]))
+        }
+
+        Mp3::new(Cursor::new(stream))
+    }
+
+    /// The number of samples per channel that are left to play.
+    fn remaining(mp3: &mut Mp3<Cursor<Vec<u8>>>) -> u64 {
+        mp3.by_ref().count() as u64 / 2
+    }
+
+    fn secs(samples: u64) -> Duration {
+        Duration::from_secs_f64(samples as f64 / 44100.)
+    }
+
+    #[test]
+    fn starts_silent() {
+        let mut mp3 = stream(info(10, None), 10);
+
+        assert!(mp3.starts_silent(Duration::from_millis(50)));
+        // nothing was skipped by looking ahead
+        assert_eq!(remaining(&mut mp3), 10 * FRAME_LEN);
+    }
+
+    #[test]
+    fn trim() {
+        // a delay of 576 and padding of 1234
+        let mut mp3 = stream(info(10, Some([0x24, 0x04, 0xD2])), 10);
+
+        let samples = 10 * FRAME_LEN - 1234 - 576;
+
+        assert_eq!(remaining(&mut mp3), samples);
+        assert_eq!(mp3.total_duration(), Some(secs(samples)));
+    }
+
+    #[test]
+    fn total_duration() {
+        let mut mp3 = stream(info(10, None), 10);
+
+        assert_eq!(mp3.total_duration(), None);
+        mp3.next();
+        assert_eq!(mp3.total_duration(), Some(secs(10 * FRAME_LEN)));
+
+        let mut vbri = b"VBRI".to_vec();
+        vbri.extend_from_slice(&[0, 1, 0x04, 0x81, 0, 75]);
+        vbri.extend_from_slice(&(11 * FRAME_BYTES as u32).to_be_bytes());
+        vbri.extend_from_slice(&10u32.to_be_bytes());
+        // a single entry of 2 bytes for all frames
+        for field in [1u16, 1, 2, 10, 11 * FRAME_BYTES as u16].iter() {
+            vbri.extend_from_slice(&field.to_be_bytes());
+        }
+
+        let mut mp3 = stream(frame(&vbri), 10);
+        mp3.next();
+
+        assert_eq!(mp3.total_duration(), Some(secs(10 * FRAME_LEN)));
+    }
+
+    #[test]
+    fn jump() {
+        let frames = 500;
+        let mut mp3 = stream(info(frames, None), frames as _);
+        mp3.next();
+
+        let pos = Duration::from_secs(12);
+        mp3.seek(pos).unwrap();
+
+        // the frames in between were skipped
+        assert!(mp3.cache.decoded_until(Duration::from_secs(6)) < Duration::from_secs(1));
+
+        let expected = u64::from(frames) * FRAME_LEN - (pos.as_secs_f64() * 44100.) as u64;
+        let remaining = remaining(&mut mp3);
+
+        assert!(
+            remaining.max(expected) - remaining.min(expected) <= FRAME_LEN,
+            "{} samples left instead of {}",
+            remaining,
+            expected
+        );
+    }
+}
@@ -35,6 +35,8 @@ impl ops::DerefMut for SampleBuf {
 
 pub struct Samples {
     samples: SampleBuf,
+    /// The first sample that's played, after those that are skipped.
+    first: u16,
     current: u16,
 }
 
@@ -48,6 +50,7 @@ impl Samples {
     pub fn new(samples: SampleBuf) -> Samples {
         Samples {
             samples,
+            first: 0,
             current: 0,
         }
     }
@@ -56,8 +59,26 @@ impl Samples {
         self.samples.len() as _
     }
 
-    pub fn set_current(&mut self, pos: u16) {
-        self.current = pos
+    /// Drops the samples from `len` on.
+    pub fn truncate(&mut self, len: u16) {
+        self.samples.set_len(len.min(self.len()));
+        self.first = self.first.min(self.len())
+    }
+
+    /// The number of samples that are played.
+    pub fn audible(&self) -> u16 {
+        self.len() - self.first
+    }
+
+    /// Skips the first `len` samples, which aren't played.
+    pub fn skip_start(&mut self, len: u16) {
+        self.first = len.min(self.len());
+        self.current = self.first
+    }
+
+    /// Continues at `pos` among the samples that are played.
+    pub fn seek(&mut self, pos: u16) {
+        self.current = self.first + pos
     }
 
     pub fn into_buf(self) -> SampleBuf {
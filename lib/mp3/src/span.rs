@@ -59,9 +59,10 @@ impl FrameSpan {
         Duration::from_micros(self.end().into())
     }
 
-    pub fn shift_back(mut self) -> FrameSpan {
-        self.start -= self.len();
-        self
+    /// Returns an empty span right where this one starts,
+    /// so that this one is the span that follows it.
+    pub fn preceding(self) -> FrameSpan {
+        FrameSpan::new(self.start, 0)
     }
 }
 
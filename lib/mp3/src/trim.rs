@@ -0,0 +1,59 @@
+use crate::{header::Header, Frame};
+
+/// Removes the silence the encoder added around the audio, so
+/// consecutive tracks join without a gap.
+#[derive(Debug, Copy, Clone)]
+pub struct Trim {
+    frame_len: u64,
+    /// The first sample of the audio, per channel.
+    start: u64,
+    /// The sample after the last one of the audio,
+    /// if the number of frames is known.
+    end: Option<u64>,
+}
+
+impl Trim {
+    /// The number of samples decoders lag behind the encoder.
+    const DECODER_DELAY: u64 = 528 + 1;
+
+    pub fn new(header: &Header) -> Option<Trim> {
+        let lame = header.lame?;
+        let frame_len = u64::from(header.frame_len);
+
+        Some(Trim {
+            frame_len,
+            start: u64::from(lame.delay) + Trim::DECODER_DELAY,
+            end: header.frames.map(|frames| {
+                (u64::from(frames) * frame_len + Trim::DECODER_DELAY)
+                    .saturating_sub(lame.padding.into())
+            }),
+        })
+    }
+
+    /// The number of samples per channel before the audio starts.
+    pub fn start(&self) -> u64 {
+        self.start
+    }
+
+    /// The number of samples per channel of the audio, if it's known.
+    pub fn len(&self) -> Option<u64> {
+        Some(self.end?.saturating_sub(self.start))
+    }
+
+    /// Cuts off the samples of the frame at the provided
+    /// index among the audio frames that are silence.
+    pub fn apply(&self, frame: &mut Frame, index: u64) {
+        let channels = u64::from(frame.channels);
+        let start = index * self.frame_len;
+
+        if let Some(end) = self.end {
+            let len = end.saturating_sub(start).min(self.frame_len) * channels;
+            frame.samples.truncate(len as _)
+        }
+
+        if start < self.start {
+            let skip = (self.start - start).min(self.frame_len) * channels;
+            frame.samples.skip_start(skip as _)
+        }
+    }
+}
@@ -0,0 +1,202 @@
+use {
+    rodio::{Sample, Source},
+    std::{
+        mem,
+        sync::{
+            atomic::{AtomicU64, Ordering},
+            Arc,
+            Mutex,
+            MutexGuard,
+        },
+        time::Duration,
+    },
+};
+
+/// The time a source has been playing for, shared between
+/// the source and whoever wants to know about it.
+#[derive(Default, Clone)]
+pub struct Clock {
+    inner: Arc<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    /// The samples played since the format last changed.
+    samples: AtomicU64,
+    format: Mutex<Format>,
+}
+
+#[derive(Default, Copy, Clone)]
+struct Format {
+    /// The time played before the format last changed.
+    base: Duration,
+    sample_rate: u32,
+    channels: u16,
+}
+
+impl Format {
+    fn duration(&self, samples: u64) -> Duration {
+        let rate = u64::from(self.sample_rate) * u64::from(self.channels);
+
+        if rate == 0 {
+            return self.base
+        }
+
+        let secs = samples / rate;
+        let nanos = (samples % rate) * 1_000_000_000 / rate;
+
+        self.base + Duration::new(secs, nanos as u32)
+    }
+}
+
+impl Clock {
+    fn format(&self) -> MutexGuard<'_, Format> {
+        self.inner.format.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn advance(&self) {
+        self.inner.samples.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn get(&self) -> Duration {
+        let format = self.format();
+        format.duration(self.inner.samples.load(Ordering::Relaxed))
+    }
+
+    pub fn set(&self, elapsed: Duration) {
+        let mut format = self.format();
+
+        format.base = elapsed;
+        self.inner.samples.store(0, Ordering::Relaxed);
+    }
+
+    /// Keeps counting at a different sample rate or number of channels.
+    fn set_format(&self, sample_rate: u32, channels: u16) {
+        let mut format = self.format();
+        let samples = self.inner.samples.swap(0, Ordering::Relaxed);
+
+        *format = Format {
+            base: format.duration(samples),
+            sample_rate,
+            channels,
+        };
+    }
+}
+
+/// Tracks the elapsed time of a `Source`
+/// by counting the samples it has played.
+pub struct Timed<S> {
+    source: S,
+    sample_rate: u32,
+    channels: u16,
+    clock: Clock,
+}
+
+impl<S> Timed<S>
+where
+    S: Source + Iterator,
+    S::Item: Sample,
+{
+    pub fn new(source: S, clock: Clock) -> Timed<S> {
+        let (sample_rate, channels) = (source.sample_rate(), source.channels());
+        clock.set_format(sample_rate, channels);
+
+        Timed {
+            source,
+            sample_rate,
+            channels,
+            clock,
+        }
+    }
+
+    fn update_format(&mut self) {
+        let (sample_rate, channels) = (self.source.sample_rate(), self.source.channels());
+
+        if (sample_rate, channels) != (self.sample_rate, self.channels) {
+            self.clock.set_format(sample_rate, channels);
+            self.sample_rate = sample_rate;
+            self.channels = channels;
+        }
+    }
+
+    /// Continues with another source, counting its time from zero,
+    /// and returns the previous one.
+    pub fn restart(&mut self, source: S) -> S {
+        let clock = self.clock.clone();
+        clock.set(Duration::ZERO);
+
+        mem::replace(self, Timed::new(source, clock)).source
+    }
+
+    /// Returns how long the source has been playing.
+    pub fn elapsed(&self) -> Duration {
+        self.clock.get()
+    }
+
+    pub fn into_inner(self) -> S {
+        self.source
+    }
+}
+
+impl<S> Iterator for Timed<S>
+where
+    S: Source + Iterator,
+    S::Item: Sample,
+{
+    type Item = S::Item;
+
+    fn next(&mut self) -> Option<S::Item> {
+        self.update_format();
+
+        let sample = self.source.next()?;
+        self.clock.advance();
+
+        Some(sample)
+    }
+}
+
+impl<S> Source for Timed<S>
+where
+    S: Source + Iterator,
+    S::Item: Sample,
+{
+    fn current_frame_len(&self) -> Option<usize> {
+        self.source.current_frame_len()
+    }
+
+    fn channels(&self) -> u16 {
+        self.source.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.source.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.source.total_duration()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {super::*, rodio::buffer::SamplesBuffer};
+
+    #[test]
+    fn elapsed() {
+        let clock = Clock::default();
+        let source = SamplesBuffer::new(2, 48000, vec![0i16; 48000 * 2]);
+        let mut timed = Timed::new(source, clock.clone());
+
+        timed.by_ref().take(3 * 2).for_each(drop);
+        assert_eq!(clock.get(), Duration::from_nanos(62_500));
+
+        clock.set(Duration::from_secs(1));
+        timed.by_ref().take(48000).for_each(drop);
+        assert_eq!(timed.elapsed(), Duration::from_millis(1500));
+
+        let next = SamplesBuffer::new(1, 8000, vec![0i16; 100]);
+        timed.restart(next);
+        timed.by_ref().take(4).for_each(drop);
+        assert_eq!(clock.get(), Duration::from_micros(500));
+    }
+}
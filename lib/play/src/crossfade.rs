@@ -0,0 +1,96 @@
+use {
+    rodio::{Sample, Source},
+    std::{f32::consts::FRAC_PI_2, time::Duration},
+};
+
+/// When and how long to fade from one sound into the next.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Crossfade {
+    /// How far into the current sound the next one starts fading in,
+    /// unless the current one knows how long it is, in which case
+    /// the fade ends with it.
+    pub at: Duration,
+    pub len: Duration,
+}
+
+/// Mixes the tail of a sound that's fading out with the head of the
+/// one fading in, keeping the loudness the same throughout.
+pub struct Fade<S> {
+    outgoing: S,
+    pos: u64,
+    len: u64,
+}
+
+impl<S> Fade<S>
+where
+    S: Source + Iterator,
+    S::Item: Sample,
+{
+    pub fn new(outgoing: S, len: Duration) -> Fade<S> {
+        let rate = outgoing.sample_rate() as f64 * outgoing.channels() as f64;
+
+        Fade {
+            outgoing,
+            pos: 0,
+            len: (len.as_secs_f64() * rate).max(1.) as u64,
+        }
+    }
+
+    /// Returns whether the outgoing sound has faded out completely.
+    pub fn is_over(&self) -> bool {
+        self.pos >= self.len
+    }
+
+    /// Mixes the next sample of the outgoing sound into the one provided.
+    pub fn mix(&mut self, incoming: Option<S::Item>) -> Option<S::Item> {
+        let (fade_out, fade_in) = equal_power(self.pos as f32 / self.len as f32);
+        self.pos += 1;
+
+        match (self.outgoing.next(), incoming) {
+            (Some(outgoing), Some(incoming)) => Some(
+                outgoing
+                    .amplify(fade_out)
+                    .saturating_add(incoming.amplify(fade_in)),
+            ),
+            (Some(outgoing), None) => Some(outgoing.amplify(fade_out)),
+            (None, incoming) => incoming.map(|incoming| incoming.amplify(fade_in)),
+        }
+    }
+}
+
+/// Returns the gains of the outgoing and incoming sound at the provided
+/// progress through the fade, whose squares always add up to one.
+fn equal_power(progress: f32) -> (f32, f32) {
+    let angle = progress.clamp(0., 1.) * FRAC_PI_2;
+
+    (angle.cos(), angle.sin())
+}
+
+#[cfg(test)]
+mod test {
+    use {super::*, rodio::buffer::SamplesBuffer};
+
+    #[test]
+    fn fade() {
+        let outgoing = SamplesBuffer::new(2, 1000, vec![1f32; 100]);
+        let mut fade = Fade::new(outgoing, Duration::from_millis(20));
+
+        // the incoming sound is silent at first
+        assert_eq!(fade.mix(Some(1.)), Some(1.));
+
+        while !fade.is_over() {
+            fade.mix(Some(0.));
+        }
+
+        let last = fade.mix(Some(1.)).unwrap();
+        assert!((last - 1.).abs() < 1e-6);
+    }
+
+    #[test]
+    fn equal_power() {
+        for step in 0..=10 {
+            let (fade_out, fade_in) = super::equal_power(step as f32 / 10.);
+            assert!((fade_out.powi(2) + fade_in.powi(2) - 1.).abs() < 1e-6);
+        }
+    }
+}
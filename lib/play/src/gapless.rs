@@ -0,0 +1,248 @@
+use {
+    crate::{
+        clock::Timed,
+        crossfade::{Crossfade, Fade},
+    },
+    rodio::{Sample, Source},
+    std::{
+        sync::{Arc, Mutex, MutexGuard},
+        time::Duration,
+    },
+};
+
+/// Plays a source and, once it's exhausted, the one queued after
+/// it, without any silence in between. If the queued source is
+/// supposed to be crossfaded, it's mixed in before that.
+pub struct Gapless<S> {
+    current: Timed<S>,
+    queue: Queue<S>,
+    /// The previous source, while it's fading out.
+    fade: Option<Fade<S>>,
+    /// The number of samples until the queue is checked for a crossfade.
+    countdown: u32,
+}
+
+/// The source that plays after the current one of a [Gapless] source,
+/// shared with whoever controls it.
+pub struct Queue<S> {
+    slot: Arc<Mutex<Slot<S>>>,
+}
+
+impl<S> Clone for Queue<S> {
+    fn clone(&self) -> Self {
+        Queue {
+            slot: self.slot.clone(),
+        }
+    }
+}
+
+struct Slot<S> {
+    next: Option<S>,
+    crossfade: Option<Crossfade>,
+    /// The number of times the queued source started playing
+    /// since it was last checked.
+    advanced: u32,
+    /// Whether the last source is over and nothing was queued in time.
+    finished: bool,
+}
+
+impl<S> Default for Queue<S> {
+    fn default() -> Self {
+        Queue {
+            slot: Arc::new(Mutex::new(Slot {
+                next: None,
+                crossfade: None,
+                advanced: 0,
+                finished: false,
+            })),
+        }
+    }
+}
+
+impl<S> Queue<S> {
+    fn lock(&self) -> MutexGuard<'_, Slot<S>> {
+        self.slot.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Queues a source to play once the current one is over, or to fade
+    /// in before that, replacing the one that was queued before. Hands the
+    /// source back if it's too late for that, because the current one is
+    /// over already.
+    pub fn enqueue(&self, source: S, crossfade: Option<Crossfade>) -> Result<(), S> {
+        let mut slot = self.lock();
+
+        if slot.finished {
+            return Err(source)
+        }
+
+        slot.next = Some(source);
+        slot.crossfade = crossfade;
+
+        Ok(())
+    }
+
+    /// Removes the queued source, if there is one,
+    /// along with how it was supposed to fade in.
+    pub fn dequeue(&self) -> Option<(S, Option<Crossfade>)> {
+        let mut slot = self.lock();
+        let next = slot.next.take()?;
+
+        Some((next, slot.crossfade.take()))
+    }
+
+    pub fn is_queued(&self) -> bool {
+        self.lock().next.is_some()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.lock().finished
+    }
+
+    /// Returns whether the queued source started playing since the last call.
+    pub fn advanced(&self) -> bool {
+        let mut slot = self.lock();
+        let advanced = slot.advanced > 0;
+        slot.advanced = 0;
+
+        advanced
+    }
+
+    fn pop(&self) -> Option<S> {
+        let mut slot = self.lock();
+
+        match slot.next.take() {
+            Some(next) => {
+                slot.advanced += 1;
+                slot.crossfade = None;
+                Some(next)
+            }
+            None => {
+                slot.finished = true;
+                None
+            }
+        }
+    }
+}
+
+impl<S> Queue<S>
+where
+    S: Source + Iterator,
+    S::Item: Sample,
+{
+    /// Takes the queued source if it's time to fade it in, judging by how
+    /// long the current one has been playing and, if it's known, how long
+    /// it is in total, and returns how long the fade takes. Sources with a
+    /// different format than the current one are left to play after it
+    /// instead, since they can't be mixed.
+    fn pop_crossfade(&self, current: &Timed<S>) -> Option<(S, Duration)> {
+        let mut slot = self.lock();
+
+        let crossfade = slot.crossfade?;
+        let next = slot.next.as_ref()?;
+
+        let at = current
+            .total_duration()
+            .map(|total| total.saturating_sub(crossfade.len))
+            .unwrap_or(crossfade.at);
+
+        if at > current.elapsed()
+            || next.channels() != current.channels()
+            || next.sample_rate() != current.sample_rate()
+        {
+            return None
+        }
+
+        slot.advanced += 1;
+        slot.crossfade = None;
+
+        Some((slot.next.take()?, crossfade.len))
+    }
+}
+
+impl<S> Gapless<S>
+where
+    S: Source + Iterator,
+    S::Item: Sample,
+{
+    /// The number of samples between checks for a crossfade.
+    const CHECK_INTERVAL: u32 = 1024;
+
+    pub fn new(current: Timed<S>, queue: Queue<S>) -> Gapless<S> {
+        Gapless {
+            current,
+            queue,
+            fade: None,
+            countdown: 0,
+        }
+    }
+
+    pub fn into_inner(self) -> S {
+        self.current.into_inner()
+    }
+
+    fn maybe_crossfade(&mut self) {
+        if self.countdown > 0 {
+            self.countdown -= 1;
+            return
+        }
+
+        self.countdown = Self::CHECK_INTERVAL;
+
+        if let Some((next, len)) = self.queue.pop_crossfade(&self.current) {
+            let outgoing = self.current.restart(next);
+            self.fade = Some(Fade::new(outgoing, len))
+        }
+    }
+}
+
+impl<S> Iterator for Gapless<S>
+where
+    S: Source + Iterator,
+    S::Item: Sample,
+{
+    type Item = S::Item;
+
+    fn next(&mut self) -> Option<S::Item> {
+        if let Some(fade) = self.fade.as_mut() {
+            let mixed = fade.mix(self.current.next());
+
+            if fade.is_over() || mixed.is_none() {
+                self.fade = None
+            }
+
+            if mixed.is_some() {
+                return mixed
+            }
+        }
+
+        self.maybe_crossfade();
+
+        self.current.next().or_else(|| {
+            let next = self.queue.pop()?;
+            self.current.restart(next);
+            self.current.next()
+        })
+    }
+}
+
+impl<S> Source for Gapless<S>
+where
+    S: Source + Iterator,
+    S::Item: Sample,
+{
+    fn current_frame_len(&self) -> Option<usize> {
+        self.current.current_frame_len()
+    }
+
+    fn channels(&self) -> u16 {
+        self.current.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.current.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
@@ -1,13 +1,15 @@
+mod clock;
+mod crossfade;
+mod gapless;
 mod handle;
 mod recover;
-mod tick;
 mod track;
 
 use {
     handle::Handle,
     rodio::{Sample, Source},
     snafu::{ResultExt, Snafu},
-    std::{fmt, time::Duration},
+    std::{fmt, mem, time::Duration},
     track::Track,
 };
 
@@ -19,17 +21,25 @@ pub enum Error {
     Stream { source: StreamError },
 }
 
-pub use rodio::{PlayError, StreamError};
+pub use {
+    crossfade::Crossfade,
+    rodio::{PlayError, StreamError},
+};
 
 /// Controls playback of a sound on the default output device
 /// and tracks its time.
 ///
 /// Also allows for updating the device it's playing to, should
 /// it need to change.
+///
+/// A sound can be queued to follow the current one without a gap.
 pub struct Player<S> {
     handle: Option<Handle>,
     current: Option<Track<S>>,
     volume: f32,
+    /// Whether a queued sound was played on its own,
+    /// because the current one was over already.
+    advanced: bool,
 }
 
 impl<S> Default for Player<S> {
@@ -38,6 +48,7 @@ impl<S> Default for Player<S> {
             handle: None,
             current: None,
             volume: 1.,
+            advanced: false,
         }
     }
 }
@@ -60,6 +71,44 @@ where
         Ok(())
     }
 
+    /// Queues the provided sound to play right after the current one, or
+    /// to fade into it if `crossfade` is provided, replacing the one queued
+    /// before. Plays it right away if the current one is over already.
+    pub fn enqueue(&mut self, sound: S, crossfade: Option<Crossfade>) -> Result<(), Error> {
+        let sound = match &self.current {
+            Some(track) => match track.enqueue(sound, crossfade) {
+                Ok(()) => return Ok(()),
+                Err(sound) => sound,
+            },
+            None => sound,
+        };
+
+        self.play(sound)?;
+        self.advanced = true;
+
+        Ok(())
+    }
+
+    /// Removes the sound queued to play after the current one.
+    pub fn dequeue(&mut self) {
+        self.mut_track(|track| {
+            track.dequeue();
+        })
+    }
+
+    /// Returns whether a sound is queued to play after the current one.
+    pub fn is_queued(&self) -> bool {
+        self.ref_track(Track::is_queued).unwrap_or_default()
+    }
+
+    /// Returns whether the queued sound started playing since the last call,
+    /// which makes it the current one.
+    pub fn advanced(&mut self) -> bool {
+        let queued = self.ref_track(Track::advanced).unwrap_or_default();
+
+        mem::take(&mut self.advanced) || queued
+    }
+
     fn set_source(&mut self, source: S) -> Result<(), Error> {
         let sink = self.init_handle()?.new_sink()?;
 
@@ -90,6 +139,7 @@ where
     /// No effect if nothing is playing.
     pub fn stop(&mut self) {
         self.current = None;
+        self.advanced = false;
     }
 
     /// Pauses the current track.
@@ -150,13 +200,34 @@ where
         self.current.is_none()
     }
 
-    /// Returns whether the current track has been playing for
-    /// the provided duration.
-    pub fn passed(&self, duration: Duration) -> bool {
-        duration
-            .checked_sub(self.elapsed())
-            .map(|diff| diff <= Track::<S>::TICK_INTERVAL)
-            .unwrap_or(true)
+    /// Returns whether the current sound has played to its end
+    /// without another one queued to follow it.
+    pub fn is_finished(&self) -> bool {
+        self.ref_track(Track::is_finished).unwrap_or_default()
+    }
+
+    /// Plays the provided sound from the specified duration, or only loads
+    /// it there if `paused`, so nothing before that is ever heard.
+    pub fn play_at(
+        &mut self,
+        mut sound: S,
+        duration: Duration,
+        paused: bool,
+    ) -> Result<(), SeekError<S::Error>>
+    where
+        S: seek::SeekableSource,
+        S::Error: fmt::Display + snafu::Error,
+    {
+        let elapsed = sound.seek(duration).context(Seek)?;
+
+        self.set_source(sound)?;
+        self.mut_track(|track| track.set_elapsed(elapsed));
+
+        if !paused {
+            self.resume()
+        }
+
+        Ok(())
     }
 
     /// Seeks to the specified duration in the current track, if one exists.
@@ -168,6 +239,7 @@ where
         let paused = self.is_paused();
 
         if let Some(track) = self.current.take() {
+            let queued = track.dequeue();
             let mut source = track.into_source().map_err(|_| SeekError::ResumePlayback)?;
 
             let elapsed = source.seek(duration).context(Seek)?;
@@ -179,6 +251,10 @@ where
             }?;
 
             self.mut_track(|track| track.set_elapsed(elapsed));
+
+            if let (Some(track), Some((queued, crossfade))) = (&self.current, queued) {
+                let _ = track.enqueue(queued, crossfade);
+            }
         }
 
         Ok(())
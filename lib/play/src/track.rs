@@ -1,7 +1,9 @@
 use {
     crate::{
+        clock::{Clock, Timed},
+        crossfade::Crossfade,
+        gapless::{Gapless, Queue},
         recover::{Recoverable, Retriever},
-        tick::{Ticking, Ticks},
     },
     rodio::{Sample, Sink, Source},
     std::time::Duration,
@@ -14,8 +16,9 @@ pub struct CannotResume;
 /// Controls playback of a sound and tracks its time.
 pub struct Track<S> {
     sink: Sink,
-    retriever: Retriever<Ticking<S>>,
-    ticks: Ticks,
+    retriever: Retriever<Gapless<S>>,
+    clock: Clock,
+    queue: Queue<S>,
 }
 
 impl<S> Track<S>
@@ -23,14 +26,13 @@ where
     S: Source + Iterator + Send + 'static,
     S::Item: Sample + Send,
 {
-    pub const TICK_INTERVAL: Duration = Duration::from_millis(100);
-
     /// Creates a new Track using the provided `Sink`
     /// and prepares the provided `Source`.
     pub fn new(sink: Sink, source: S) -> Track<S> {
-        let ticks = Ticks::default();
-        let source = Ticking::new(source, Self::TICK_INTERVAL, ticks.clone());
-        let (source, retriever) = Recoverable::new(source);
+        let clock = Clock::default();
+        let queue = Queue::default();
+        let source = Timed::new(source, clock.clone());
+        let (source, retriever) = Recoverable::new(Gapless::new(source, queue.clone()));
 
         sink.pause();
         sink.append(source);
@@ -38,10 +40,35 @@ where
         Track {
             sink,
             retriever,
-            ticks,
+            clock,
+            queue,
         }
     }
 
+    /// Queues a sound to play once this one is over or to fade in before
+    /// that, handing it back if this one is over already.
+    pub fn enqueue(&self, source: S, crossfade: Option<Crossfade>) -> Result<(), S> {
+        self.queue.enqueue(source, crossfade)
+    }
+
+    pub fn dequeue(&self) -> Option<(S, Option<Crossfade>)> {
+        self.queue.dequeue()
+    }
+
+    pub fn is_queued(&self) -> bool {
+        self.queue.is_queued()
+    }
+
+    /// Returns whether the sound is over and nothing was queued to follow it.
+    pub fn is_finished(&self) -> bool {
+        self.queue.is_finished()
+    }
+
+    /// Returns whether the queued sound started playing since the last call.
+    pub fn advanced(&self) -> bool {
+        self.queue.advanced()
+    }
+
     /// Starts or resumes playing the track. No effect if it's already playing.
     pub fn play(&mut self) {
         self.sink.play()
@@ -59,7 +86,7 @@ where
 
     /// Returns how long this track has been playing.
     pub fn elapsed(&self) -> Duration {
-        Self::TICK_INTERVAL * self.ticks.get()
+        self.clock.get()
     }
 
     /// Attempts to resume playback on a different `Sink`.
@@ -92,9 +119,7 @@ where
 
     /// Sets the elapsed time directly.
     pub fn set_elapsed(&mut self, elapsed: Duration) {
-        let ticks = elapsed.as_millis() / Self::TICK_INTERVAL.as_millis();
-
-        self.ticks.set(ticks as _)
+        self.clock.set(elapsed)
     }
 
     /// Consumes the track and returns the source it was playing.
@@ -104,7 +129,7 @@ where
 
         self.retriever
             .wait()
-            .map(Ticking::into_inner)
+            .map(Gapless::into_inner)
             .map_err(|_| CannotResume)
     }
 }
@@ -0,0 +1,76 @@
+use {
+    crate::{Request, Response},
+    snafu::{ResultExt, Snafu},
+    std::{
+        io::{self, BufRead, BufReader, Write},
+        path::Path,
+    },
+};
+
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("couldn't connect to justbc, is it running? ({})", source))]
+    Connect { source: io::Error },
+    #[snafu(display("error communicating with justbc: {}", source))]
+    Communicate { source: io::Error },
+    #[snafu(display("error encoding request: {}", source))]
+    Encode { source: serde_json::Error },
+    #[snafu(display("received an invalid response: {}", source))]
+    Decode { source: serde_json::Error },
+}
+
+/// Sends a request to the instance listening at the provided
+/// path and waits for its response.
+pub fn send(path: impl AsRef<Path>, request: &Request) -> Result<Response, Error> {
+    #[cfg(not(unix))]
+    let mut stream = crate::unsupported::connect(path).context(Connect)?;
+    #[cfg(unix)]
+    let mut stream = UnixStream::connect(path).context(Connect)?;
+
+    let mut json = serde_json::to_vec(request).context(Encode)?;
+    json.push(b'\n');
+    stream.write_all(&json).context(Communicate)?;
+
+    let mut line = String::new();
+    BufReader::new(stream)
+        .read_line(&mut line)
+        .context(Communicate)?;
+
+    serde_json::from_str(&line).context(Decode)
+}
+
+#[cfg(all(test, unix))]
+mod test {
+    use {super::*, crate::Server, std::thread};
+
+    #[test]
+    fn roundtrip() {
+        let path = std::env::temp_dir().join(format!("justbc-test-{}.sock", std::process::id()));
+        let server = Server::bind(&path).unwrap();
+
+        let client = thread::spawn({
+            let path = path.clone();
+            move || send(path, &Request::Status).unwrap()
+        });
+
+        let call = loop {
+            if let Some(call) = server.calls().next() {
+                break call
+            }
+
+            thread::yield_now()
+        };
+
+        assert_eq!(call.request, Request::Status);
+        call.reply(Response::Ok);
+
+        assert_eq!(client.join().unwrap(), Response::Ok);
+        assert!(Server::bind(&path).is_err());
+
+        drop(server);
+        assert!(!path.exists());
+    }
+}
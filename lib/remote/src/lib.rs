@@ -0,0 +1,135 @@
+//! Controlling a running instance from the outside, using JSON
+//! requests and responses sent over a Unix domain socket, one per line.
+
+mod client;
+mod position;
+#[cfg(unix)]
+mod server;
+#[cfg(not(unix))]
+mod unsupported;
+
+pub use {client::*, position::Position};
+
+#[cfg(unix)]
+pub use server::Server;
+
+#[cfg(not(unix))]
+pub use unsupported::Server;
+
+use {
+    bandcamp_api::data::releases::ReleaseKind,
+    serde::{Deserialize, Serialize},
+    std::sync::mpsc,
+};
+
+/// A request that's waiting to be answered.
+pub struct Call {
+    pub request: Request,
+    reply: mpsc::Sender<Response>,
+}
+
+impl Call {
+    pub fn reply(self, response: Response) {
+        // the client might have hung up already,
+        // in which case there's no one to answer
+        let _ = self.reply.send(response);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum Request {
+    /// Does the same as pressing the key bound to the command.
+    Command {
+        command: input::Command,
+    },
+    /// Fetches a release and starts playing one of its tracks.
+    Play {
+        release: u64,
+        outlet: u64,
+        #[serde(default = "album")]
+        kind: ReleaseKind,
+        #[serde(default)]
+        track: usize,
+    },
+    Seek {
+        position: Position,
+    },
+    Volume {
+        volume: f32,
+    },
+    Status,
+}
+
+fn album() -> ReleaseKind {
+    ReleaseKind::Album
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum Response {
+    Ok,
+    Error { message: String },
+    Status(Status),
+}
+
+impl Response {
+    pub fn error(message: impl Into<String>) -> Response {
+        Response::Error {
+            message: message.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Status {
+    pub paused: bool,
+    pub elapsed: Position,
+    /// The index of the current track in the queue.
+    pub current: Option<usize>,
+    pub queue: Vec<Track>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Track {
+    pub title: String,
+    pub artist: String,
+    pub album: String,
+    pub duration: Position,
+}
+
+#[cfg(test)]
+mod test {
+    use {super::*, std::time::Duration};
+
+    #[test]
+    fn requests() {
+        let parse = |json| serde_json::from_str::<Request>(json).unwrap();
+
+        assert_eq!(
+            parse(r#"{"type": "command", "command": "toggle-play"}"#),
+            Request::Command {
+                command: input::Command::TogglePlay
+            }
+        );
+        assert_eq!(
+            parse(r#"{"type": "play", "release": 1, "outlet": 2, "track": 3}"#),
+            Request::Play {
+                release: 1,
+                outlet: 2,
+                kind: ReleaseKind::Album,
+                track: 3
+            }
+        );
+        assert_eq!(
+            parse(r#"{"type": "seek", "position": "1:23"}"#),
+            Request::Seek {
+                position: Position(Duration::from_secs(83))
+            }
+        );
+        assert_eq!(
+            parse(r#"{"type": "volume", "volume": 0.4}"#),
+            Request::Volume { volume: 0.4 }
+        );
+    }
+}
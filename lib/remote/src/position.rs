@@ -0,0 +1,115 @@
+use {
+    serde::{de, Deserialize, Deserializer, Serialize, Serializer},
+    std::{fmt, str::FromStr, time::Duration},
+};
+
+/// A point in a track. Written as a number of seconds,
+/// or as a timestamp like `1:23` or `1:02:03`.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Position(pub Duration);
+
+impl FromStr for Position {
+    type Err = InvalidPosition;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(secs) = s.parse::<f64>() {
+            return from_secs(secs)
+        }
+
+        let mut parts = s.rsplit(':');
+
+        let secs = parts
+            .next()
+            .and_then(|secs| secs.parse::<f64>().ok())
+            .filter(|secs| *secs < 60.)
+            .ok_or(InvalidPosition)?;
+
+        let minutes = parts
+            .next()
+            .and_then(|min| min.parse::<u64>().ok())
+            .ok_or(InvalidPosition)?;
+
+        let hours = match parts.next() {
+            Some(hours) if minutes < 60 => hours.parse::<u64>().map_err(|_| InvalidPosition)?,
+            Some(_) => return Err(InvalidPosition),
+            None => 0,
+        };
+
+        if parts.next().is_some() {
+            return Err(InvalidPosition)
+        }
+
+        from_secs(((hours * 60 + minutes) * 60) as f64 + secs)
+    }
+}
+
+fn from_secs(secs: f64) -> Result<Position, InvalidPosition> {
+    if secs.is_finite() && secs >= 0. {
+        Ok(Position(Duration::from_secs_f64(secs)))
+    } else {
+        Err(InvalidPosition)
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let secs = self.0.as_secs();
+
+        write!(f, "{}:{:02}", secs / 60, secs % 60)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct InvalidPosition;
+
+impl fmt::Display for InvalidPosition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("expected a number of seconds or a timestamp like 1:23")
+    }
+}
+
+impl std::error::Error for InvalidPosition {}
+
+impl Serialize for Position {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(self.0.as_secs_f64())
+    }
+}
+
+impl<'de> Deserialize<'de> for Position {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Secs(f64),
+            Timestamp(String),
+        }
+
+        match Repr::deserialize(deserializer)? {
+            Repr::Secs(secs) => from_secs(secs),
+            Repr::Timestamp(ts) => ts.parse(),
+        }
+        .map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn secs(s: &str) -> Option<u64> {
+        s.parse::<Position>().ok().map(|pos| pos.0.as_secs())
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(secs("83"), Some(83));
+        assert_eq!(secs("1:23"), Some(83));
+        assert_eq!(secs("01:02:03"), Some(3723));
+        assert_eq!(secs("1:60"), None);
+        assert_eq!(secs("1:60:00"), None);
+        assert_eq!(secs("-1"), None);
+        assert_eq!(secs("1:2:3:4"), None);
+        assert_eq!(secs("soon"), None);
+    }
+}
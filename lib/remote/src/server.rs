@@ -0,0 +1,101 @@
+use {
+    crate::{Call, Response},
+    std::{
+        fs,
+        io::{self, BufRead, BufReader, Write},
+        os::unix::net::{UnixListener, UnixStream},
+        path::PathBuf,
+        sync::mpsc,
+        thread,
+    },
+};
+
+/// Listens on a socket and hands out the requests it receives.
+/// The socket file is removed when this is dropped.
+pub struct Server {
+    calls: mpsc::Receiver<Call>,
+    path: PathBuf,
+}
+
+impl Server {
+    /// Starts listening on a socket at the provided path.
+    ///
+    /// A socket file that was left behind by an instance that didn't shut
+    /// down properly is replaced, while one that's still in use results
+    /// in an error.
+    pub fn bind(path: impl Into<PathBuf>) -> io::Result<Server> {
+        let path = path.into();
+
+        if path.exists() {
+            if UnixStream::connect(&path).is_ok() {
+                return Err(io::Error::new(
+                    io::ErrorKind::AddrInUse,
+                    "another instance is already listening",
+                ))
+            }
+
+            fs::remove_file(&path)?
+        }
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?
+        }
+
+        let listener = UnixListener::bind(&path)?;
+        let (sender, calls) = mpsc::channel();
+
+        thread::spawn(move || accept(listener, sender));
+
+        Ok(Server { calls, path })
+    }
+
+    /// Returns the requests received since the last call.
+    pub fn calls(&self) -> impl Iterator<Item = Call> + '_ {
+        self.calls.try_iter()
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+fn accept(listener: UnixListener, calls: mpsc::Sender<Call>) {
+    for stream in listener.incoming().flatten() {
+        let calls = calls.clone();
+        thread::spawn(move || serve(stream, calls));
+    }
+}
+
+fn serve(stream: UnixStream, calls: mpsc::Sender<Call>) -> io::Result<()> {
+    let mut writer = stream.try_clone()?;
+
+    for line in BufReader::new(stream).lines() {
+        let line = line?;
+
+        if line.trim().is_empty() {
+            continue
+        }
+
+        let response = match serde_json::from_str(&line) {
+            Ok(request) => {
+                let (reply, response) = mpsc::channel();
+
+                if calls.send(Call { request, reply }).is_err() {
+                    return Ok(())
+                }
+
+                response
+                    .recv()
+                    .unwrap_or_else(|_| Response::error("no response"))
+            }
+            Err(e) => Response::error(format!("invalid request: {}", e)),
+        };
+
+        serde_json::to_writer(&mut writer, &response)?;
+        writer.write_all(b"\n")?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use {
+    crate::Call,
+    std::{
+        io,
+        net::TcpStream,
+        path::{Path, PathBuf},
+    },
+};
+
+fn unsupported() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Other,
+        "remote control requires Unix domain sockets",
+    )
+}
+
+pub struct Server;
+
+impl Server {
+    pub fn bind(_: impl Into<PathBuf>) -> io::Result<Server> {
+        Err(unsupported())
+    }
+
+    pub fn calls(&self) -> impl Iterator<Item = Call> + '_ {
+        std::iter::empty()
+    }
+}
+
+pub fn connect(_: impl AsRef<Path>) -> io::Result<TcpStream> {
+    Err(unsupported())
+}
@@ -37,7 +37,7 @@ impl StreamCursor {
     }
 
     fn ready(&self) -> usize {
-        self.end().checked_sub(self.pos).unwrap_or_default()
+        self.end().saturating_sub(self.pos)
     }
 
     fn consumed(&self) -> usize {